@@ -9,8 +9,101 @@ pub const MARKETPLACE_FEE: &[u8] = b"FEE";
 #[constant]
 pub const LISTING: &[u8] = b"MARKETPLACE_LISTING";
 
+#[constant]
+pub const BUNDLE_LISTING: &[u8] = b"MARKETPLACE_BUNDLE_LISTING";
+
+#[constant]
+pub const SWAP: &[u8] = b"MARKETPLACE_SWAP";
+
+#[constant]
+pub const CLAIMABLE_BALANCE: &[u8] = b"MARKETPLACE_CLAIMABLE_BALANCE";
+
+#[constant]
+pub const COLLECTION_STATS: &[u8] = b"MARKETPLACE_COLLECTION_STATS";
+
+#[constant]
+pub const PRICE_OBSERVATIONS: &[u8] = b"MARKETPLACE_PRICE_OBSERVATIONS";
+
+#[constant]
+pub const MULTISIG: &[u8] = b"MARKETPLACE_MULTISIG";
+
+#[constant]
+pub const MULTISIG_PROPOSAL: &[u8] = b"MARKETPLACE_MULTISIG_PROPOSAL";
+
+#[constant]
+pub const PENDING_CHANGE: &[u8] = b"MARKETPLACE_PENDING_CHANGE";
+
 #[constant]
 pub const SIGNER: &[u8] = b"MARKETPLACE_SIGNER";
 
+#[constant]
+pub const MAX_ACCEPTED_CURRENCIES: usize = 5;
+
+// A wallet, or a `Multisig` account approving fee withdrawals
 #[constant]
 pub const FEE_OWNER: &str = "3BiVpSVqGw9VX9Dp1SdBvKaGwBtWEhpG8eWkfLPZyMhK";
+
+#[constant]
+pub const ROYALTY_BASIS_POINTS_KEY: &str = "seller_fee_basis_points";
+
+// Program owning the price feed accounts USD-pegged listings are settled through
+#[constant]
+pub const PRICE_FEED_PROGRAM: &str = "7M4Yh5KvRZnE4jhAsRCUrzFfCmMLur5ENgqMQD5vB9i5";
+
+#[constant]
+pub const MAX_PRICE_FEED_AGE: i64 = 60;
+
+#[constant]
+pub const MAX_PRICE_FEED_CONFIDENCE_BASIS_POINTS: u64 = 200;
+
+#[constant]
+pub const MAX_BATCH_LISTING_ITEMS: usize = 10;
+
+#[constant]
+pub const BATCH_LISTING_ACCOUNTS_LEN: usize = 5;
+
+#[constant]
+pub const MAX_SWEEP_ITEMS: usize = 5;
+
+#[constant]
+pub const SWEEP_ACCOUNTS_LEN: usize = 9;
+
+#[constant]
+pub const MAX_BUNDLE_ITEMS: usize = 5;
+
+#[constant]
+pub const BUNDLE_LISTING_ACCOUNTS_LEN: usize = 4;
+
+#[constant]
+pub const BUNDLE_SALE_ACCOUNTS_LEN: usize = 6;
+
+#[constant]
+pub const CANCEL_BUNDLE_ACCOUNTS_LEN: usize = 2;
+
+#[constant]
+pub const MAX_SWAP_ASSETS: usize = 4;
+
+#[constant]
+pub const SWAP_LEG_ACCOUNTS_LEN: usize = 2;
+
+#[constant]
+pub const MAX_FEE_TIERS: usize = 4;
+
+#[constant]
+pub const MAX_FEE_SPLITS: usize = 5;
+
+#[constant]
+pub const MAX_PRICE_OBSERVATIONS: usize = 64;
+
+#[constant]
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+#[constant]
+pub const MAX_PROPOSAL_ACCOUNTS: usize = 16;
+
+#[constant]
+pub const MAX_PROPOSAL_DATA_LEN: usize = 512;
+
+// Longest delay, in seconds, a marketplace can hold fee changes for
+#[constant]
+pub const MAX_CHANGE_DELAY: i64 = 30 * 24 * 60 * 60;
@@ -11,6 +11,9 @@ use crate::external::{
     spl_token::{
       is_token_program
     },
+    spl_token_2022::{
+      is_supported_token_program,
+    },
   };
 
 #[derive(Accounts)]
@@ -100,27 +103,36 @@ pub struct InitMarketplaceContext<'info> {
         seeds = [MARKETPLACE, &owner.key().as_ref(), &spl_token_mint.key().as_ref()],
         bump,
         payer = payer,
-        space = 8 + 32 + 32 + 2 + 32 + 8 + 1,
+        space = MarketplaceNFT::LEN,
     )]
     pub marketplace: Account<'info, MarketplaceNFT>,
 
-    /// CHECK: The mint address of the token to be used as the Marketplace currency
-    #[account(mut)]
+    /// CHECK: The mint address of the token to be used as the Marketplace currency (SPL Token or Token-2022)
+    #[account(
+        mut,
+        constraint = is_supported_token_program(spl_token_mint.owner) @TokenProgramNotSupported,
+    )]
     pub spl_token_mint: AccountInfo<'info>,
 
     /// CHECK: Associated token account for the fee account
     #[account(
         mut,
-        constraint = fee_account.owner.to_string() == FEE_OWNER @FeeAccountNotOwner,
-        constraint = fee_account.mint.to_string() == spl_token_mint.key().to_string() @FeeAccountNotOwner,
+        owner = *spl_token_mint.owner @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.owner.to_string() == FEE_OWNER @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.mint.to_string() == spl_token_mint.key().to_string() @FeeAccountNotOwner,
     )]
-    pub fee_account: Account<'info, TokenAccount>,
+    pub fee_account: AccountInfo<'info>,
 
-    /// CHECK: The public key of the Marketplace instance creator
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        seeds=[MARKETPLACE, marketplace.key().as_ref(), SIGNER],
+        bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
 
-    /// CHECK: Bump seed for the Marketplace instance
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -129,176 +141,1298 @@ pub struct InitMarketplaceContext<'info> {
 }
 
 #[derive(Accounts)]
-#[instruction(list_price: u8)]
-pub struct ListingNftContext<'info> {
+pub struct SetAcceptedCurrencyContext<'info> {
 
     #[account(
-        init,
-        seeds = [
-            LISTING,
-            &seller.key().as_ref(),
-            &[list_price],
-            mint_nft_account.key().as_ref(),
-            nft_marketplace_account.key().as_ref(),
-            nft_token_account.key().as_ref(),
-            token_mint_account.key().as_ref(),
-        ],
-        bump,
-        payer = seller,
-        space = 8 + 32 + 1 + 8 + 32 + 32 + 32 + 32,
+        mut,
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
     )]
-    pub seller_trade_state: Account<'info, SellerTradeState>,
+    pub marketplace: Account<'info, MarketplaceNFT>,
 
-    /// CHECK: The public key of the seller listing NFT to marketplace
-    #[account(mut)]
-    pub seller: Signer<'info>,
+    /// CHECK: The mint address of the currency to accept (SPL Token or Token-2022)
+    #[account(
+        constraint = is_supported_token_program(spl_token_mint.owner) @TokenProgramNotSupported,
+    )]
+    pub spl_token_mint: AccountInfo<'info>,
 
-    /// CHECK: The public key of mint NFT being listing to marketplace
-    #[account(mut)]
-    pub mint_nft_account: AccountInfo<'info>,
+    /// CHECK: Associated token account for the fee account
+    #[account(
+        owner = *spl_token_mint.owner @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.owner.to_string() == FEE_OWNER @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.mint.to_string() == spl_token_mint.key().to_string() @FeeAccountNotOwner,
+    )]
+    pub fee_account: AccountInfo<'info>,
 
-    /// CHECK: The public key of Marketplace account instance
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateMarketplaceContext<'info> {
+
+    /// CHECK: Marketplace in any layout, deserialized in instruction
     #[account(
         mut,
-        seeds=[MARKETPLACE, &authority.key().as_ref(), &token_mint_account.key().as_ref()],
-        bump
+        owner = crate::ID @NFTMarketplaceAccountNotCorrect,
     )]
-    pub nft_marketplace_account: AccountInfo<'info>,
+    pub marketplace: AccountInfo<'info>,
 
-    /// CHECK: The public key of NFT token account
     #[account(mut)]
-    pub nft_token_account: AccountInfo<'info>,
+    pub payer: Signer<'info>,
 
-    /// CHECK: The mint address of the token to be used as the Marketplace currency
-    #[account(mut)]
-    pub token_mint_account: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
 
-    /// CHECK: The public key of the Marketplace instance creator
-    #[account(mut)]
-    pub authority: AccountInfo<'info>,
+#[derive(Accounts)]
+pub struct MigrateListingContext<'info> {
 
-    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// CHECK: Seller trade state in any layout, deserialized in instruction
     #[account(
         mut,
-        seeds=[MARKETPLACE, SIGNER],
-        bump
+        owner = crate::ID @SellerTradeStateNotCorrect,
     )]
-    pub program_as_signer: UncheckedAccount<'info>,
+    pub seller_trade_state: AccountInfo<'info>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct RemoveAcceptedCurrencyContext<'info> {
+
+    #[account(
+        mut,
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub marketplace: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The mint address of the currency to remove
+    pub spl_token_mint: AccountInfo<'info>,
+
+    /// CHECK: Fee account of the currency to remove, emptiness checked in instruction
+    #[account(
+        constraint = marketplace.get_accepted_currency(&spl_token_mint.key()).map_or(false, |currency| currency.fee_account == fee_account.key()) @FeeAccountNotOwner,
+    )]
+    pub fee_account: AccountInfo<'info>,
+
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+}
 
 #[derive(Accounts)]
-#[instruction(amount: u8)]
-pub struct BuyNftContext<'info> {
+pub struct SetListPriceBoundsContext<'info> {
 
-    /// CHECK: The public key of the buyer
-    #[account(mut)]
-    pub buyer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.get_accepted_currency(&spl_token_mint.key()).is_some() @CurrencyNotAccepted,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub marketplace: Account<'info, MarketplaceNFT>,
 
-    /// CHECK: The public key of the seller listing NFT to marketplace
-    #[account(mut)]
-    pub seller: AccountInfo<'info>,
+    /// CHECK: The mint address of the currency the bounds apply to
+    pub spl_token_mint: AccountInfo<'info>,
 
-    /// CHECK: Associated token account of buyer to store NFT
-    #[account(mut)]
-    pub buyer_nft_account: AccountInfo<'info>,
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+}
 
-    /// CHECK: Associated token account of buyer to store money
-    #[account(mut)]
-    pub buyer_token_account: AccountInfo<'info>,
+#[derive(Accounts)]
+pub struct SetPriceFeedContext<'info> {
 
-    /// CHECK: Associated token account of seller to store money
-    #[account(mut)]
-    pub seller_token_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.get_accepted_currency(&spl_token_mint.key()).is_some() @CurrencyNotAccepted,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub marketplace: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The mint address of the currency the price feed prices
+    pub spl_token_mint: AccountInfo<'info>,
+
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTokenGateContext<'info> {
 
-    /// CHECK: Seller trade state account
     #[account(
         mut,
-        seeds = [
-            LISTING,
-            &seller.key().as_ref(),
-            &[amount],
-            mint_nft_account.key().as_ref(),
-            nft_marketplace_account.key().as_ref(),
-            nft_token_account.key().as_ref(),
-            token_mint_account.key().as_ref(),
-        ],
-        bump,
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
     )]
-    pub seller_trade_state: Account<'info, SellerTradeState>,
+    pub marketplace: Account<'info, MarketplaceNFT>,
 
-    /// CHECK: The public key of mint NFT being listing to marketplace
-    #[account(mut)]
-    pub mint_nft_account: AccountInfo<'info>,
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetReferralBasisPointsContext<'info> {
 
-    /// CHECK: The public key of Marketplace account instance
     #[account(
         mut,
-        seeds = [MARKETPLACE, &authority.key().as_ref(), &token_mint_account.key().as_ref()],
-        bump,
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
     )]
-    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+    pub marketplace: Account<'info, MarketplaceNFT>,
 
-    /// CHECK: The public key of associated token account of seller
-    #[account(mut)]
-    pub nft_token_account: AccountInfo<'info>,
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+}
 
-    /// CHECK: The mint address of the token to be used as the Marketplace currency
-    #[account(mut)]
-    pub token_mint_account: AccountInfo<'info>,
+#[derive(Accounts)]
+pub struct SetRoyaltyPolicyContext<'info> {
 
-    /// CHECK: Not dangerous. Account seeds checked in constraint.
     #[account(
         mut,
-        seeds=[MARKETPLACE, SIGNER],
-        bump
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
     )]
-    pub program_as_signer: UncheckedAccount<'info>,
+    pub marketplace: Account<'info, MarketplaceNFT>,
 
-    /// CHECK: Creator of marketplace instance
-    #[account(mut)]
-    pub authority: AccountInfo<'info>,
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeTiersContext<'info> {
 
-    /// CHECK: Associated token account for the fee account
     #[account(
         mut,
-        constraint = fee_account.owner.to_string() == FEE_OWNER @FeeAccountNotOwner,
-        constraint = fee_account.mint.to_string() == token_mint_account.key().to_string() @FeeAccountNotOwner,
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
     )]
-    pub fee_account: Account<'info, TokenAccount>,
+    pub marketplace: Account<'info, MarketplaceNFT>,
 
-    /// CHECK: Token program ID (default = TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA)
-    pub token_program: AccountInfo<'info>,
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+}
 
-    pub system_program: Program<'info, System>,
+#[derive(Accounts)]
+pub struct SetFeeSplitsContext<'info> {
+
+    #[account(
+        mut,
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub marketplace: Account<'info, MarketplaceNFT>,
 
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-pub struct WithdrawFromFeeAccountContext<'info> {
+pub struct SetChangeDelayContext<'info> {
 
-    /// CHECK: Associated token account for the fee account
     #[account(
         mut,
-        constraint = fee_account.owner.to_string() == FEE_OWNER @FeeAccountNotOwner,
-        constraint = fee_account.mint.to_string() == token_mint_account.key().to_string() @FeeAccountNotOwner,
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
     )]
-    pub fee_account: Account<'info, TokenAccount>,
+    pub marketplace: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueuePendingChangeContext<'info> {
+
+    #[account(
+        init,
+        seeds = [PENDING_CHANGE, &marketplace.key().as_ref(), &spl_token_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PendingChange::LEN,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    #[account(
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub marketplace: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The mint address of the accepted currency to change
+    pub spl_token_mint: AccountInfo<'info>,
+
+    /// CHECK: Associated token account for the fee account
+    #[account(
+        owner = *spl_token_mint.owner @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.owner.to_string() == FEE_OWNER @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.mint.to_string() == spl_token_mint.key().to_string() @FeeAccountNotOwner,
+    )]
+    pub fee_account: AccountInfo<'info>,
+
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
 
-    /// CHECK: The public key of the Marketplace instance creator
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPendingChangeContext<'info> {
+
+    #[account(
+        mut,
+        has_one = marketplace,
+        has_one = payer,
+        close = payer,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    #[account(
+        seeds = [MARKETPLACE, &owner.key().as_ref(), &marketplace.spl_token_mint.as_ref()],
+        bump = marketplace.bump,
+        has_one = owner,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub marketplace: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The public key of the Marketplace instance creator, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
 
+    /// CHECK: The payer of the pending change, refunded its rent
     #[account(mut)]
-    pub destination_account: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+}
 
-    /// CHECK: The mint address of the token to be used as the Marketplace currency
+#[derive(Accounts)]
+pub struct ApplyPendingChangeContext<'info> {
+
+    #[account(
+        mut,
+        has_one = marketplace,
+        has_one = payer,
+        close = payer,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    #[account(
+        mut,
+        constraint = marketplace.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub marketplace: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The payer of the pending change, refunded its rent
     #[account(mut)]
+    pub payer: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(collection: Pubkey)]
+pub struct InitCollectionStatsContext<'info> {
+
+    #[account(
+        init,
+        seeds = [
+            COLLECTION_STATS,
+            nft_marketplace_account.key().as_ref(),
+            collection.as_ref(),
+            token_mint_account.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = CollectionStats::LEN,
+    )]
+    pub collection_stats: Account<'info, CollectionStats>,
+
+    #[account(
+        init,
+        seeds = [PRICE_OBSERVATIONS, collection_stats.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PriceObservations::LEN,
+    )]
+    pub price_observations: Account<'info, PriceObservations>,
+
+    /// CHECK: The public key of Marketplace account instance
+    #[account(
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).is_some() @CurrencyNotAccepted,
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The mint address of the currency the stats record sales in
     pub token_mint_account: AccountInfo<'info>,
 
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+}
+
+#[derive(Accounts)]
+pub struct InitPriceObservationsContext<'info> {
+
+    #[account(
+        init,
+        seeds = [PRICE_OBSERVATIONS, collection_stats.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PriceObservations::LEN,
+    )]
+    pub price_observations: Account<'info, PriceObservations>,
+
+    pub collection_stats: Account<'info, CollectionStats>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+}
+
+#[derive(Accounts)]
+pub struct GetTwapContext<'info> {
+
+    pub price_observations: Account<'info, PriceObservations>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteSaleContext<'info> {
+
+    /// CHECK: The public key of Marketplace account instance
+    #[account(
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).is_some() @CurrencyNotAccepted,
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The mint address of the currency of the sale
+    pub token_mint_account: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(list_price: u8)]
+pub struct ListingNftContext<'info> {
+
+    #[account(
+        init,
+        seeds = [
+            LISTING,
+            &seller.key().as_ref(),
+            &[list_price],
+            mint_nft_account.key().as_ref(),
+            nft_marketplace_account.key().as_ref(),
+            nft_token_account.key().as_ref(),
+            token_mint_account.key().as_ref(),
+        ],
+        bump,
+        payer = seller,
+        space = SellerTradeState::LEN,
+    )]
+    pub seller_trade_state: Account<'info, SellerTradeState>,
+
+    /// CHECK: The public key of the seller listing NFT to marketplace
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: The public key of mint NFT being listing to marketplace
+    #[account(mut)]
+    pub mint_nft_account: AccountInfo<'info>,
+
+    /// CHECK: The public key of Marketplace account instance
+    #[account(
+        mut,
+        seeds=[MARKETPLACE, &authority.key().as_ref(), &nft_marketplace_account.spl_token_mint.as_ref()],
+        bump = nft_marketplace_account.bump,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).is_some() @CurrencyNotAccepted,
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The public key of NFT token account
+    #[account(mut)]
+    pub nft_token_account: AccountInfo<'info>,
+
+    /// CHECK: The mint address of the token to be used as the Marketplace currency
+    #[account(mut)]
+    pub token_mint_account: AccountInfo<'info>,
+
+    /// CHECK: The public key of the Marketplace instance creator
+    #[account(mut)]
+    pub authority: AccountInfo<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        mut,
+        seeds=[MARKETPLACE, nft_marketplace_account.key().as_ref(), SIGNER],
+        bump = nft_marketplace_account.signer_bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Token program owning the NFT mint (SPL Token or Token-2022)
+    #[account(
+        constraint = is_supported_token_program(token_program.key) @TokenProgramNotSupported,
+        constraint = token_program.key() == *mint_nft_account.owner @TokenProgramNotCorrect,
+    )]
+    pub token_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Price feed trusted by the currency for USD-pegged listings, checked in instruction
+    pub price_feed: Option<AccountInfo<'info>>,
+
+    /// CHECK: Token Metadata account of the NFT, required unless the NFT is a Token-2022 NFT, checked in instruction
+    pub metadata_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Stats address of the verified collection of the NFT, required for collection members, checked in instruction
+    #[account(mut)]
+    pub collection_stats: Option<AccountInfo<'info>>,
+
+    /// CHECK: Token account of seller holding the fee tier asset, checked in instruction
+    pub seller_fee_tier_token_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Token Metadata account of the NFT in `seller_fee_tier_token_account` for collection tiers
+    pub seller_fee_tier_metadata_account: Option<AccountInfo<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct BatchListingNftContext<'info> {
+
+    /// CHECK: The public key of the seller listing NFTs to marketplace
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: The public key of Marketplace account instance
+    #[account(
+        mut,
+        seeds=[MARKETPLACE, &authority.key().as_ref(), &nft_marketplace_account.spl_token_mint.as_ref()],
+        bump = nft_marketplace_account.bump,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).is_some() @CurrencyNotAccepted,
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The mint address of the token to be used as the Marketplace currency
+    #[account(mut)]
+    pub token_mint_account: AccountInfo<'info>,
+
+    /// CHECK: The public key of the Marketplace instance creator
+    #[account(mut)]
+    pub authority: AccountInfo<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        mut,
+        seeds=[MARKETPLACE, nft_marketplace_account.key().as_ref(), SIGNER],
+        bump = nft_marketplace_account.signer_bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Token program owning every NFT mint of the batch (SPL Token or Token-2022)
+    #[account(
+        constraint = is_supported_token_program(token_program.key) @TokenProgramNotSupported,
+    )]
+    pub token_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Token account of seller holding the fee tier asset, checked in instruction
+    pub seller_fee_tier_token_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Token Metadata account of the NFT in `seller_fee_tier_token_account` for collection tiers
+    pub seller_fee_tier_metadata_account: Option<AccountInfo<'info>>,
+}
+
+
+#[derive(Accounts)]
+#[instruction(amount: u8)]
+pub struct BuyNftContext<'info> {
+
+    /// CHECK: The public key of the buyer
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: The public key of the seller listing NFT to marketplace
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+
+    /// CHECK: Associated token account of buyer to store NFT
+    #[account(mut)]
+    pub buyer_nft_account: AccountInfo<'info>,
+
+    /// CHECK: Associated token account of buyer to store money
+    #[account(mut)]
+    pub buyer_token_account: AccountInfo<'info>,
+
+    /// CHECK: Currency token account of seller receiving the proceeds, unless the listing pays into the vault
+    #[account(
+        mut,
+        constraint = TokenAccount::try_from_account_info(seller_token_account)?.owner == seller.key() @SellerTokenAccountNotCorrect,
+        constraint = TokenAccount::try_from_account_info(seller_token_account)?.mint == token_mint_account.key() @SellerTokenAccountNotCorrect,
+    )]
+    pub seller_token_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Seller trade state account
+    #[account(
+        mut,
+        seeds = [
+            LISTING,
+            &seller.key().as_ref(),
+            &[amount],
+            mint_nft_account.key().as_ref(),
+            nft_marketplace_account.key().as_ref(),
+            nft_token_account.key().as_ref(),
+            token_mint_account.key().as_ref(),
+        ],
+        bump = seller_trade_state.bump,
+        close = seller,
+        constraint = seller_trade_state.reserved_buyer.map_or(true, |reserved_buyer| reserved_buyer == buyer.key()) @ListingReserved,
+        constraint = seller_trade_state.version == SellerTradeState::VERSION @AccountVersionNotSupported,
+    )]
+    pub seller_trade_state: Account<'info, SellerTradeState>,
+
+    /// CHECK: The public key of mint NFT being listing to marketplace
+    #[account(mut)]
+    pub mint_nft_account: AccountInfo<'info>,
+
+    /// CHECK: The public key of Marketplace account instance
+    #[account(
+        mut,
+        seeds = [MARKETPLACE, &authority.key().as_ref(), &nft_marketplace_account.spl_token_mint.as_ref()],
+        bump = nft_marketplace_account.bump,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).is_some() @CurrencyNotAccepted,
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The public key of associated token account of seller
+    #[account(mut)]
+    pub nft_token_account: AccountInfo<'info>,
+
+    /// CHECK: The mint address of the token to be used as the Marketplace currency
+    #[account(mut)]
+    pub token_mint_account: AccountInfo<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        mut,
+        seeds=[MARKETPLACE, nft_marketplace_account.key().as_ref(), SIGNER],
+        bump = nft_marketplace_account.signer_bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Creator of marketplace instance
+    #[account(mut)]
+    pub authority: AccountInfo<'info>,
+
+    /// CHECK: Associated token account for the fee account
+    #[account(
+        mut,
+        owner = *token_mint_account.owner @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.owner.to_string() == FEE_OWNER @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.mint.to_string() == token_mint_account.key().to_string() @FeeAccountNotOwner,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).map(|currency| currency.fee_account) == Some(fee_account.key()) @FeeAccountNotOwner,
+    )]
+    pub fee_account: AccountInfo<'info>,
+
+    /// CHECK: Token program owning the NFT mint (SPL Token or Token-2022)
+    #[account(
+        constraint = is_supported_token_program(token_program.key) @TokenProgramNotSupported,
+        constraint = token_program.key() == *mint_nft_account.owner @TokenProgramNotCorrect,
+    )]
+    pub token_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Token program owning the currency mint (SPL Token or Token-2022)
+    #[account(
+        constraint = currency_token_program.key() == *token_mint_account.owner @TokenProgramNotCorrect,
+    )]
+    pub currency_token_program: AccountInfo<'info>,
+
+    /// CHECK: Price feed for USD-pegged listings, checked against the listing and the currency in instruction
+    pub price_feed: Option<AccountInfo<'info>>,
+
+    /// CHECK: Token account of buyer proving a gated listing requirement, checked in instruction
+    pub gate_token_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Token Metadata account of the NFT in `gate_token_account` for collection gates
+    pub gate_metadata_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Currency token account of the referrer routing the buyer, paid a share of the fee. The referrer
+    /// cannot be the buyer or the seller
+    #[account(
+        mut,
+        constraint = TokenAccount::try_from_account_info(referrer_token_account)?.mint == token_mint_account.key() @ReferrerAccountNotCorrect,
+        constraint = TokenAccount::try_from_account_info(referrer_token_account)?.owner != buyer.key() @ReferrerAccountNotCorrect,
+        constraint = TokenAccount::try_from_account_info(referrer_token_account)?.owner != seller.key() @ReferrerAccountNotCorrect,
+    )]
+    pub referrer_token_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Token account of buyer holding the fee tier asset, checked in instruction
+    pub buyer_fee_tier_token_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Token Metadata account of the NFT in `buyer_fee_tier_token_account` for collection tiers
+    pub buyer_fee_tier_metadata_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Token Metadata account of the NFT sold, required unless the NFT is a Token-2022 NFT
+    pub metadata_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Currency token account of the program signer holding claimable balances, for listings paying into the vault
+    #[account(
+        mut,
+        constraint = TokenAccount::try_from_account_info(vault_token_account)?.owner == program_as_signer.key() @VaultAccountNotCorrect,
+        constraint = TokenAccount::try_from_account_info(vault_token_account)?.mint == token_mint_account.key() @VaultAccountNotCorrect,
+    )]
+    pub vault_token_account: Option<AccountInfo<'info>>,
+
+    /// CHECK: Claimable balance of seller credited with the proceeds, created if needed, checked in instruction
+    #[account(mut)]
+    pub seller_claimable_balance: Option<AccountInfo<'info>>,
+
+    /// CHECK: Stats address of the verified collection of the NFT, required for collection members, checked in instruction
+    #[account(mut)]
+    pub collection_stats: Option<AccountInfo<'info>>,
+
+    /// CHECK: Price observations of `collection_stats`, required when the collection has stats, checked in instruction
+    #[account(mut)]
+    pub price_observations: Option<AccountInfo<'info>>,
+
+}
+
+#[derive(Accounts)]
+pub struct CancelListingContext<'info> {
+
+    #[account(
+        mut,
+        close = seller,
+        has_one = seller,
+        has_one = nft_token_account,
+        has_one = mint_nft_account @MintNFTAccountNotCorrect,
+        constraint = seller_trade_state.version == SellerTradeState::VERSION @AccountVersionNotSupported,
+    )]
+    pub seller_trade_state: Account<'info, SellerTradeState>,
+
+    /// CHECK: The public key of the seller who listed the NFT
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: The public key of the NFT token account of the listing
+    #[account(mut)]
+    pub nft_token_account: AccountInfo<'info>,
+
+    /// CHECK: The mint address of the NFT of the listing
+    pub mint_nft_account: AccountInfo<'info>,
+
+    #[account(
+        constraint = nft_marketplace_account.key() == seller_trade_state.nft_marketplace_account @NFTMarketplaceAccountNotCorrect,
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        seeds=[MARKETPLACE, nft_marketplace_account.key().as_ref(), SIGNER],
+        bump = nft_marketplace_account.signer_bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Token program owning the NFT token account (SPL Token or Token-2022)
+    #[account(
+        constraint = is_supported_token_program(token_program.key) @TokenProgramNotSupported,
+        constraint = token_program.key() == *nft_token_account.owner @TokenProgramNotCorrect,
+    )]
+    pub token_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Stats of the collection counting the listing, required when the listing is counted
+    #[account(
+        mut,
+        constraint = Some(collection_stats.collection) == seller_trade_state.collection @CollectionStatsNotCorrect,
+        constraint = collection_stats.nft_marketplace_account == seller_trade_state.nft_marketplace_account @CollectionStatsNotCorrect,
+        constraint = collection_stats.token_mint_account == seller_trade_state.token_mint_account @CollectionStatsNotCorrect,
+    )]
+    pub collection_stats: Option<Account<'info, CollectionStats>>,
+
+}
+
+#[derive(Accounts)]
+pub struct SweepContext<'info> {
+
+    /// CHECK: The public key of the buyer
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Associated token account of buyer to store money
+    #[account(mut)]
+    pub buyer_token_account: AccountInfo<'info>,
+
+    /// CHECK: The public key of Marketplace account instance
+    #[account(
+        mut,
+        seeds = [MARKETPLACE, &authority.key().as_ref(), &nft_marketplace_account.spl_token_mint.as_ref()],
+        bump = nft_marketplace_account.bump,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).is_some() @CurrencyNotAccepted,
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The mint address of the token to be used as the Marketplace currency
+    #[account(mut)]
+    pub token_mint_account: AccountInfo<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        mut,
+        seeds=[MARKETPLACE, nft_marketplace_account.key().as_ref(), SIGNER],
+        bump = nft_marketplace_account.signer_bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Creator of marketplace instance
+    #[account(mut)]
+    pub authority: AccountInfo<'info>,
+
+    /// CHECK: Associated token account for the fee account
+    #[account(
+        mut,
+        owner = *token_mint_account.owner @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.owner.to_string() == FEE_OWNER @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.mint.to_string() == token_mint_account.key().to_string() @FeeAccountNotOwner,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).map(|currency| currency.fee_account) == Some(fee_account.key()) @FeeAccountNotOwner,
+    )]
+    pub fee_account: AccountInfo<'info>,
+
+    /// CHECK: Token program owning every NFT mint of the sweep (SPL Token or Token-2022)
+    #[account(
+        constraint = is_supported_token_program(token_program.key) @TokenProgramNotSupported,
+    )]
+    pub token_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Token program owning the currency mint (SPL Token or Token-2022)
+    #[account(
+        constraint = currency_token_program.key() == *token_mint_account.owner @TokenProgramNotCorrect,
+    )]
+    pub currency_token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(bundle_id: u64)]
+pub struct ListBundleContext<'info> {
+
+    #[account(
+        init,
+        seeds = [
+            BUNDLE_LISTING,
+            &seller.key().as_ref(),
+            nft_marketplace_account.key().as_ref(),
+            &bundle_id.to_le_bytes(),
+        ],
+        bump,
+        payer = seller,
+        space = BundleListing::LEN,
+    )]
+    pub bundle_listing: Account<'info, BundleListing>,
+
+    /// CHECK: The public key of the seller listing the bundle to marketplace
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// CHECK: The public key of Marketplace account instance
+    #[account(
+        mut,
+        seeds=[MARKETPLACE, &authority.key().as_ref(), &nft_marketplace_account.spl_token_mint.as_ref()],
+        bump = nft_marketplace_account.bump,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).is_some() @CurrencyNotAccepted,
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The mint address of the token to be used as the Marketplace currency
+    #[account(mut)]
+    pub token_mint_account: AccountInfo<'info>,
+
+    /// CHECK: The public key of the Marketplace instance creator
+    #[account(mut)]
+    pub authority: AccountInfo<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        mut,
+        seeds=[MARKETPLACE, nft_marketplace_account.key().as_ref(), SIGNER],
+        bump = nft_marketplace_account.signer_bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Token program owning every NFT mint of the bundle (SPL Token or Token-2022)
+    #[account(
+        constraint = is_supported_token_program(token_program.key) @TokenProgramNotSupported,
+    )]
+    pub token_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyBundleContext<'info> {
+
+    /// CHECK: The public key of the buyer
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: The public key of the seller listing the bundle to marketplace
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+
+    /// CHECK: Associated token account of buyer to store money
+    #[account(mut)]
+    pub buyer_token_account: AccountInfo<'info>,
+
+    /// CHECK: Associated token account of seller to store money
+    #[account(
+        mut,
+        constraint = TokenAccount::try_from_account_info(&seller_token_account)?.owner == bundle_listing.seller @SellerTokenAccountNotCorrect,
+        constraint = TokenAccount::try_from_account_info(&seller_token_account)?.mint == token_mint_account.key() @SellerTokenAccountNotCorrect,
+    )]
+    pub seller_token_account: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            BUNDLE_LISTING,
+            &seller.key().as_ref(),
+            nft_marketplace_account.key().as_ref(),
+            &bundle_listing.bundle_id.to_le_bytes(),
+        ],
+        bump = bundle_listing.bump,
+        has_one = seller @SellerNotCorrect,
+        has_one = nft_marketplace_account @NFTMarketplaceAccountNotCorrect,
+        has_one = token_mint_account @TokenMintAccountNotCorrect,
+        close = seller,
+    )]
+    pub bundle_listing: Account<'info, BundleListing>,
+
+    /// CHECK: The public key of Marketplace account instance
+    #[account(
+        mut,
+        seeds = [MARKETPLACE, &authority.key().as_ref(), &nft_marketplace_account.spl_token_mint.as_ref()],
+        bump = nft_marketplace_account.bump,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).is_some() @CurrencyNotAccepted,
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: The mint address of the token to be used as the Marketplace currency
+    #[account(mut)]
+    pub token_mint_account: AccountInfo<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        mut,
+        seeds=[MARKETPLACE, nft_marketplace_account.key().as_ref(), SIGNER],
+        bump = nft_marketplace_account.signer_bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Creator of marketplace instance
+    #[account(mut)]
+    pub authority: AccountInfo<'info>,
+
+    /// CHECK: Associated token account for the fee account
+    #[account(
+        mut,
+        owner = *token_mint_account.owner @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.owner.to_string() == FEE_OWNER @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.mint.to_string() == token_mint_account.key().to_string() @FeeAccountNotOwner,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).map(|currency| currency.fee_account) == Some(fee_account.key()) @FeeAccountNotOwner,
+    )]
+    pub fee_account: AccountInfo<'info>,
+
+    /// CHECK: Token program owning every NFT mint of the bundle (SPL Token or Token-2022)
+    #[account(
+        constraint = is_supported_token_program(token_program.key) @TokenProgramNotSupported,
+    )]
+    pub token_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Token program owning the currency mint (SPL Token or Token-2022)
+    #[account(
+        constraint = currency_token_program.key() == *token_mint_account.owner @TokenProgramNotCorrect,
+    )]
+    pub currency_token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelBundleContext<'info> {
+
+    #[account(
+        mut,
+        seeds = [
+            BUNDLE_LISTING,
+            &seller.key().as_ref(),
+            nft_marketplace_account.key().as_ref(),
+            &bundle_listing.bundle_id.to_le_bytes(),
+        ],
+        bump = bundle_listing.bump,
+        has_one = seller @SellerNotCorrect,
+        has_one = nft_marketplace_account @NFTMarketplaceAccountNotCorrect,
+        close = seller,
+    )]
+    pub bundle_listing: Account<'info, BundleListing>,
+
+    /// CHECK: The public key of the seller who listed the bundle
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        seeds=[MARKETPLACE, nft_marketplace_account.key().as_ref(), SIGNER],
+        bump = nft_marketplace_account.signer_bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Token program owning every NFT mint of the bundle (SPL Token or Token-2022)
+    #[account(
+        constraint = is_supported_token_program(token_program.key) @TokenProgramNotSupported,
+    )]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(swap_id: u64)]
+pub struct CreateSwapContext<'info> {
+
+    #[account(
+        init,
+        seeds = [SWAP, &maker.key().as_ref(), &swap_id.to_le_bytes()],
+        bump,
+        payer = maker,
+        space = Swap::LEN,
+    )]
+    pub swap: Account<'info, Swap>,
+
+    /// CHECK: The public key of the party depositing the offered assets
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: SPL Token program
+    #[account(
+        constraint = is_token_program(&token_program) @TokenProgramNotSupported,
+    )]
+    pub token_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptSwapContext<'info> {
+
+    #[account(
+        mut,
+        seeds = [SWAP, &maker.key().as_ref(), &swap.swap_id.to_le_bytes()],
+        bump = swap.bump,
+        has_one = maker @SwapNotCorrect,
+        has_one = taker @SwapNotCorrect,
+        close = maker,
+    )]
+    pub swap: Account<'info, Swap>,
+
+    /// CHECK: The public key of the party which deposited the offered assets
+    #[account(mut)]
+    pub maker: AccountInfo<'info>,
+
+    /// CHECK: The public key of the counterparty named by the maker
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// CHECK: SPL Token program
+    #[account(
+        constraint = is_token_program(&token_program) @TokenProgramNotSupported,
+    )]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSwapContext<'info> {
+
+    #[account(
+        mut,
+        seeds = [SWAP, &maker.key().as_ref(), &swap.swap_id.to_le_bytes()],
+        bump = swap.bump,
+        has_one = maker @SwapNotCorrect,
+        close = maker,
+    )]
+    pub swap: Account<'info, Swap>,
+
+    /// CHECK: The public key of the party which deposited the offered assets
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    /// CHECK: SPL Token program
+    #[account(
+        constraint = is_token_program(&token_program) @TokenProgramNotSupported,
+    )]
+    pub token_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFromFeeAccountContext<'info> {
+
+    /// CHECK: The public key of Marketplace account instance booking the withdrawal
+    #[account(
+        mut,
+        constraint = nft_marketplace_account.fee_splits.is_empty() @FeeSplitsNotCorrect,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).is_some() @CurrencyNotAccepted,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).map_or(false, |currency| currency.fee_account == fee_account.key()) @FeeAccountNotOwner,
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: Associated token account for the fee account
+    #[account(
+        mut,
+        owner = *token_mint_account.owner @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.owner.to_string() == FEE_OWNER @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.owner == owner.key() @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.mint.to_string() == token_mint_account.key().to_string() @FeeAccountNotOwner,
+    )]
+    pub fee_account: AccountInfo<'info>,
+
+    /// CHECK: The fee owner, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub destination_account: AccountInfo<'info>,
+
+    /// CHECK: The mint address of the token to be used as the Marketplace currency
+    #[account(mut)]
+    pub token_mint_account: AccountInfo<'info>,
+
+    /// CHECK: Token program owning the currency mint (SPL Token or Token-2022)
+    #[account(
+        constraint = token_program.key() == *token_mint_account.owner @TokenProgramNotCorrect,
+    )]
+    pub token_program: AccountInfo<'info>,
+
+}
+
+#[derive(Accounts)]
+pub struct DistributeFeesContext<'info> {
+
+    /// CHECK: The public key of Marketplace account instance whose fee splits are paid
+    #[account(
+        mut,
+        constraint = !nft_marketplace_account.fee_splits.is_empty() @FeeSplitsNotCorrect,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).is_some() @CurrencyNotAccepted,
+        constraint = nft_marketplace_account.get_accepted_currency(&token_mint_account.key()).map_or(false, |currency| currency.fee_account == fee_account.key()) @FeeAccountNotOwner,
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: Associated token account for the fee account
+    #[account(
+        mut,
+        owner = *token_mint_account.owner @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.owner.to_string() == FEE_OWNER @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.owner == owner.key() @FeeAccountNotOwner,
+        constraint = TokenAccount::try_from_account_info(&fee_account)?.mint.to_string() == token_mint_account.key().to_string() @FeeAccountNotOwner,
+    )]
+    pub fee_account: AccountInfo<'info>,
+
+    /// CHECK: The fee owner, signing or a multisig approved in instruction
+    pub owner: AccountInfo<'info>,
+
+    /// CHECK: The mint address of the currency distributed
+    pub token_mint_account: AccountInfo<'info>,
+
+    /// CHECK: Token program owning the currency mint (SPL Token or Token-2022)
+    #[account(
+        constraint = token_program.key() == *token_mint_account.owner @TokenProgramNotCorrect,
+    )]
+    pub token_program: AccountInfo<'info>,
+
+}
+
+#[derive(Accounts)]
+pub struct ClaimContext<'info> {
+
+    /// CHECK: Claimable balance of wallet
+    #[account(
+        mut,
+        seeds = [
+            CLAIMABLE_BALANCE,
+            nft_marketplace_account.key().as_ref(),
+            wallet.key().as_ref(),
+            token_mint_account.key().as_ref(),
+        ],
+        bump = claimable_balance.bump,
+        has_one = wallet,
+        has_one = nft_marketplace_account,
+        has_one = token_mint_account,
+    )]
+    pub claimable_balance: Account<'info, ClaimableBalance>,
+
+    /// CHECK: The public key claiming its balance
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// The NFT marketplace account the balance was credited on
+    #[account(
+        constraint = nft_marketplace_account.version == MarketplaceNFT::VERSION @AccountVersionNotSupported,
+    )]
+    pub nft_marketplace_account: Account<'info, MarketplaceNFT>,
+
+    /// CHECK: Currency token account of the program signer holding claimable balances
+    #[account(
+        mut,
+        constraint = TokenAccount::try_from_account_info(&vault_token_account)?.owner == program_as_signer.key() @VaultAccountNotCorrect,
+        constraint = TokenAccount::try_from_account_info(&vault_token_account)?.mint == token_mint_account.key() @VaultAccountNotCorrect,
+    )]
+    pub vault_token_account: AccountInfo<'info>,
+
+    /// CHECK: Currency token account receiving the claimed balance
+    #[account(mut)]
+    pub destination_account: AccountInfo<'info>,
+
+    /// CHECK: The mint address of the token to be used as the Marketplace currency
+    pub token_mint_account: AccountInfo<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        seeds=[MARKETPLACE, nft_marketplace_account.key().as_ref(), SIGNER],
+        bump = nft_marketplace_account.signer_bump
+    )]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    /// CHECK: Token program owning the currency mint (SPL Token or Token-2022)
+    #[account(
+        constraint = token_program.key() == *token_mint_account.owner @TokenProgramNotCorrect,
+    )]
+    pub token_program: AccountInfo<'info>,
+
+}
+
+#[derive(Accounts)]
+#[instruction(multisig_id: u64)]
+pub struct CreateMultisigContext<'info> {
+
+    #[account(
+        init,
+        seeds = [MULTISIG, &creator.key().as_ref(), &multisig_id.to_le_bytes()],
+        bump,
+        payer = creator,
+        space = Multisig::LEN,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: The public key creating the multisig, part of its address
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMultisigSignersContext<'info> {
+
+    /// CHECK: Multisig signing through an executed proposal
+    #[account(
+        mut,
+        signer @MultisigNotCorrect,
+    )]
+    pub multisig: Account<'info, Multisig>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposalContext<'info> {
+
+    #[account(
+        init,
+        seeds = [MULTISIG_PROPOSAL, &multisig.key().as_ref(), &multisig.proposal_count.to_le_bytes()],
+        bump,
+        payer = proposer,
+        space = Proposal::LEN,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        constraint = multisig.is_signer(&proposer.key()) @MultisigNotCorrect,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: Signer of the multisig proposing the instruction
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposalContext<'info> {
+
+    #[account(
+        mut,
+        has_one = multisig,
+        constraint = !proposal.executed @ProposalExecuted,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        constraint = multisig.is_signer(&signer.key()) @MultisigNotCorrect,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    /// CHECK: Signer of the multisig approving the proposal
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposalContext<'info> {
+
+    #[account(
+        mut,
+        has_one = multisig,
+        constraint = !proposal.executed @ProposalExecuted,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: Not mutable here, the proposed instruction may update it
+    pub multisig: Account<'info, Multisig>,
 }
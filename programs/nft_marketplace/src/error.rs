@@ -24,4 +24,166 @@ pub enum ErrorCode {
   #[msg("Fee account is not owner")]
   FeeAccountNotOwner,
 
+  #[msg("Programmable NFT accounts are missing")]
+  ProgrammableAccountsMissing,
+
+  #[msg("Metadata account is not correct")]
+  MetadataAccountNotCorrect,
+
+  #[msg("Edition account is not correct")]
+  EditionAccountNotCorrect,
+
+  #[msg("Token record account is not correct")]
+  TokenRecordAccountNotCorrect,
+
+  #[msg("Token Metadata program is not correct")]
+  TokenMetadataProgramNotCorrect,
+
+  #[msg("Sysvar instructions account is not correct")]
+  SysvarInstructionsNotCorrect,
+
+  #[msg("Authorization rules account is not correct")]
+  AuthorizationRulesNotCorrect,
+
+  #[msg("Token program is not supported")]
+  TokenProgramNotSupported,
+
+  #[msg("Token program is not correct")]
+  TokenProgramNotCorrect,
+
+  #[msg("Mint extension is not supported")]
+  MintExtensionNotSupported,
+
+  #[msg("NFT mint is not correct")]
+  NFTMintNotCorrect,
+
+  #[msg("Metadata pointer is not correct")]
+  MetadataPointerNotCorrect,
+
+  #[msg("Currency is not accepted by the marketplace")]
+  CurrencyNotAccepted,
+
+  #[msg("Too many accepted currencies")]
+  TooManyAcceptedCurrencies,
+
+  #[msg("Primary currency cannot be removed")]
+  PrimaryCurrencyNotRemovable,
+
+  #[msg("Price feed is not correct")]
+  PriceFeedNotCorrect,
+
+  #[msg("Price feed is stale")]
+  PriceFeedStale,
+
+  #[msg("Price feed confidence is too wide")]
+  PriceFeedNotConfident,
+
+  #[msg("Batch listing is not correct")]
+  BatchListingNotCorrect,
+
+  #[msg("Seller trade state account is not correct")]
+  SellerTradeStateNotCorrect,
+
+  #[msg("Sweep is not correct")]
+  SweepNotCorrect,
+
+  #[msg("Listing is not available")]
+  ListingNotAvailable,
+
+  #[msg("Sweep spend cap exceeded")]
+  SweepSpendCapExceeded,
+
+  #[msg("Bundle is not correct")]
+  BundleNotCorrect,
+
+  #[msg("Royalty account is not correct")]
+  RoyaltyAccountNotCorrect,
+
+  #[msg("Swap is not correct")]
+  SwapNotCorrect,
+
+  #[msg("Swap token account is not correct")]
+  SwapTokenAccountNotCorrect,
+
+  #[msg("Listing is reserved for another buyer")]
+  ListingReserved,
+
+  #[msg("Buyer does not meet the token gate")]
+  TokenGateNotMet,
+
+  #[msg("Referral basis points are not correct")]
+  ReferralBasisPointsNotCorrect,
+
+  #[msg("Referrer account is not correct")]
+  ReferrerAccountNotCorrect,
+
+  #[msg("Fee tiers are not correct")]
+  FeeTiersNotCorrect,
+
+  #[msg("Fee tier account is not correct")]
+  FeeTierAccountNotCorrect,
+
+  #[msg("Listing price is out of the marketplace bounds")]
+  ListPriceOutOfBounds,
+
+  #[msg("Royalty policy is not correct")]
+  RoyaltyPolicyNotCorrect,
+
+  #[msg("Claimable balance account is not correct")]
+  ClaimableBalanceNotCorrect,
+
+  #[msg("Vault token account is not correct")]
+  VaultAccountNotCorrect,
+
+  #[msg("Claimable balance is not enough")]
+  ClaimableBalanceNotEnough,
+
+  #[msg("Collection stats account is not correct")]
+  CollectionStatsNotCorrect,
+
+  #[msg("Price observations account is not correct")]
+  PriceObservationsNotCorrect,
+
+  #[msg("Price observations do not cover the window")]
+  TwapWindowNotCovered,
+
+  #[msg("Account version is not supported")]
+  AccountVersionNotSupported,
+
+  #[msg("Multisig is not correct")]
+  MultisigNotCorrect,
+
+  #[msg("Owner approval is not met")]
+  OwnerApprovalNotMet,
+
+  #[msg("Proposal is not correct")]
+  ProposalNotCorrect,
+
+  #[msg("Proposal is already executed")]
+  ProposalExecuted,
+
+  #[msg("Fee changes must be queued while the marketplace has a change delay")]
+  ChangeTimelocked,
+
+  #[msg("Change delay is not correct")]
+  ChangeDelayNotCorrect,
+
+  #[msg("Pending change is not effective yet")]
+  PendingChangeNotEffective,
+
+  #[msg("Fee splits are not correct")]
+  FeeSplitsNotCorrect,
+
+  #[msg("Fee recipient account is not correct")]
+  FeeRecipientNotCorrect,
+
+  #[msg("Fee basis points are not correct")]
+  FeeBasisPointsNotCorrect,
+
+  #[msg("Seller token account is not correct")]
+  SellerTokenAccountNotCorrect,
+
+  #[msg("Fee account is not empty")]
+  FeeAccountNotEmpty,
+
 }
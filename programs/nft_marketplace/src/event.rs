@@ -1 +1,63 @@
 use anchor_lang::prelude::*;
+
+use crate::state::FeeSplit;
+
+#[event]
+pub struct ListNftEvent {
+    pub seller_trade_state: Pubkey,
+    pub seller: Pubkey,
+    pub mint_nft_account: Pubkey,
+    pub nft_marketplace_account: Pubkey,
+    pub token_mint_account: Pubkey,
+    pub list_price: u64,
+    pub usd_price: Option<u64>,
+    pub reserved_buyer: Option<Pubkey>,
+}
+
+#[event]
+pub struct CancelListingEvent {
+    pub seller_trade_state: Pubkey,
+    pub seller: Pubkey,
+    pub mint_nft_account: Pubkey,
+    pub nft_marketplace_account: Pubkey,
+}
+
+#[event]
+pub struct SaleEvent {
+    pub seller_trade_state: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub mint_nft_account: Pubkey,
+    pub nft_marketplace_account: Pubkey,
+    pub token_mint_account: Pubkey,
+    pub sale_price: u64,
+    pub maker_fee: u64,
+    pub taker_fee: u64,
+    pub royalty: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
+    pub seller_fee_tier: Option<u8>,
+    pub buyer_fee_tier: Option<u8>,
+}
+
+#[event]
+pub struct ClaimEvent {
+    pub claimable_balance: Pubkey,
+    pub wallet: Pubkey,
+    pub nft_marketplace_account: Pubkey,
+    pub token_mint_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PendingChangeEvent {
+    pub pending_change: Pubkey,
+    pub nft_marketplace_account: Pubkey,
+    pub token_mint_account: Pubkey,
+    pub fee_account: Pubkey,
+    pub seller_fee_basis_points: u16,
+    pub buyer_fee_basis_points: u16,
+    pub change_delay: i64,
+    pub effective_time: i64,
+    pub fee_splits: Option<Vec<FeeSplit>>,
+}
@@ -0,0 +1,372 @@
+use anchor_lang::prelude::*;
+use mpl_token_metadata::{
+  instruction::{
+    builders::{
+      DelegateBuilder,
+      RevokeBuilder,
+      TransferBuilder,
+    },
+    DelegateArgs,
+    InstructionBuilder,
+    RevokeArgs,
+    TransferArgs,
+  },
+  pda::{
+    find_master_edition_account,
+    find_metadata_account,
+    find_token_record_account,
+  },
+  state::{
+    Metadata,
+    ProgrammableConfig,
+    TokenMetadataAccount,
+    TokenStandard,
+  },
+  ID as TOKEN_METADATA_ID,
+};
+use solana_program::{
+  program::{
+    invoke,
+    invoke_signed,
+  },
+  sysvar::{
+    instructions::{
+      ID as SYSVAR_INSTRUCTIONS_ID,
+    },
+  },
+};
+use crate::error::ErrorCode;
+
+/// Number of `remaining_accounts` needed to list a programmable NFT.
+pub const PROGRAMMABLE_LISTING_ACCOUNTS_LEN: usize = 7;
+
+/// Number of `remaining_accounts` needed to sell a programmable NFT.
+pub const PROGRAMMABLE_SALE_ACCOUNTS_LEN: usize = 9;
+
+/// Token Metadata accounts of an NFT, passed through `remaining_accounts` in this order:
+///
+///   0. `[writable]` Metadata account
+///   1. `[]` Master edition account
+///   2. `[writable]` Token record of the seller token account
+///   3. `[]` Token Metadata program
+///   4. `[]` Instructions sysvar
+///   5. `[]` Token Authorization Rules program (Token Metadata program if the NFT has no rule set)
+///   6. `[]` Token Authorization Rules account (Token Metadata program if the NFT has no rule set)
+///   7. `[writable]` Token record of the buyer token account (sale only)
+///   8. `[]` Associated Token Account program (sale only)
+pub struct ProgrammableNftAccounts<'info> {
+  pub metadata: AccountInfo<'info>,
+  pub edition: AccountInfo<'info>,
+  pub token_record: AccountInfo<'info>,
+  pub token_metadata_program: AccountInfo<'info>,
+  pub sysvar_instructions: AccountInfo<'info>,
+  pub authorization_rules_program: AccountInfo<'info>,
+  pub authorization_rules: AccountInfo<'info>,
+  pub destination_token_record: Option<AccountInfo<'info>>,
+  pub spl_ata_program: Option<AccountInfo<'info>>,
+  pub token_standard: Option<TokenStandard>,
+  pub rule_set: Option<Pubkey>,
+}
+
+impl<'info> ProgrammableNftAccounts<'info> {
+
+  /// Read and validate the Token Metadata accounts of `mint` held in `token`.
+  pub fn load(
+    accounts: &[AccountInfo<'info>],
+    mint: &Pubkey,
+    token: &Pubkey,
+  ) -> Result<Self> {
+    require!(accounts.len() >= PROGRAMMABLE_LISTING_ACCOUNTS_LEN, ErrorCode::ProgrammableAccountsMissing);
+
+    let metadata = &accounts[0];
+    let edition = &accounts[1];
+    let token_record = &accounts[2];
+    let token_metadata_program = &accounts[3];
+    let sysvar_instructions = &accounts[4];
+
+    require!(*metadata.key == find_metadata_account(mint).0, ErrorCode::MetadataAccountNotCorrect);
+    require!(*edition.key == find_master_edition_account(mint).0, ErrorCode::EditionAccountNotCorrect);
+    require!(*token_record.key == find_token_record_account(mint, token).0, ErrorCode::TokenRecordAccountNotCorrect);
+    require!(*token_metadata_program.key == TOKEN_METADATA_ID, ErrorCode::TokenMetadataProgramNotCorrect);
+    require!(*sysvar_instructions.key == SYSVAR_INSTRUCTIONS_ID, ErrorCode::SysvarInstructionsNotCorrect);
+
+    let metadata_data = Metadata::from_account_info(metadata)?;
+    let rule_set = match metadata_data.programmable_config {
+      Some(ProgrammableConfig::V1 { rule_set }) => rule_set,
+      None => None,
+    };
+    if let Some(rule_set) = rule_set {
+      require!(*accounts[6].key == rule_set, ErrorCode::AuthorizationRulesNotCorrect);
+    }
+
+    let (destination_token_record, spl_ata_program) = if accounts.len() >= PROGRAMMABLE_SALE_ACCOUNTS_LEN {
+      (Some(accounts[7].clone()), Some(accounts[8].clone()))
+    }
+    else {
+      (None, None)
+    };
+
+    Ok(ProgrammableNftAccounts {
+      metadata: metadata.clone(),
+      edition: edition.clone(),
+      token_record: token_record.clone(),
+      token_metadata_program: token_metadata_program.clone(),
+      sysvar_instructions: sysvar_instructions.clone(),
+      authorization_rules_program: accounts[5].clone(),
+      authorization_rules: accounts[6].clone(),
+      destination_token_record,
+      spl_ata_program,
+      token_standard: metadata_data.token_standard,
+      rule_set,
+    })
+  }
+
+  /// Checks if the NFT must be moved through Token Metadata instead of SPL Token
+  pub fn is_programmable(&self) -> bool {
+    matches!(self.token_standard, Some(TokenStandard::ProgrammableNonFungible))
+  }
+
+  /// Make `delegate` the sale delegate of the NFT. Token Metadata also locks the token
+  /// record in the `Listed` state, so the owner cannot move the NFT until the delegate is revoked.
+  pub fn delegate_sale(
+    &self,
+    owner: &AccountInfo<'info>,
+    token: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    delegate: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    spl_token_program: &AccountInfo<'info>,
+  ) -> Result<()> {
+    let mut builder = DelegateBuilder::new();
+    builder
+      .delegate(*delegate.key)
+      .metadata(*self.metadata.key)
+      .master_edition(*self.edition.key)
+      .token_record(*self.token_record.key)
+      .mint(*mint.key)
+      .token(*token.key)
+      .authority(*owner.key)
+      .payer(*owner.key)
+      .system_program(*system_program.key)
+      .sysvar_instructions(*self.sysvar_instructions.key)
+      .spl_token_program(*spl_token_program.key);
+    if let Some(rule_set) = self.rule_set {
+      builder
+        .authorization_rules_program(*self.authorization_rules_program.key)
+        .authorization_rules(rule_set);
+    }
+    let instruction = builder
+      .build(DelegateArgs::SaleV1 {
+        amount: 1,
+        authorization_data: None,
+      })
+      .map_err(|_| ProgramError::InvalidArgument)?
+      .instruction();
+
+    invoke(&instruction, &[
+      delegate.clone(),
+      self.metadata.clone(),
+      self.edition.clone(),
+      self.token_record.clone(),
+      mint.clone(),
+      token.clone(),
+      owner.clone(),
+      system_program.clone(),
+      self.sysvar_instructions.clone(),
+      spl_token_program.clone(),
+      self.authorization_rules_program.clone(),
+      self.authorization_rules.clone(),
+      self.token_metadata_program.clone(),
+    ])?;
+
+    Ok(())
+  }
+
+  /// Revoke the sale delegate `delegate` of the NFT, which also unlocks the token record
+  /// so the owner can move the NFT again.
+  pub fn revoke_sale(
+    &self,
+    owner: &AccountInfo<'info>,
+    token: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    delegate: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    spl_token_program: &AccountInfo<'info>,
+  ) -> Result<()> {
+    let mut builder = RevokeBuilder::new();
+    builder
+      .delegate(*delegate.key)
+      .metadata(*self.metadata.key)
+      .master_edition(*self.edition.key)
+      .token_record(*self.token_record.key)
+      .mint(*mint.key)
+      .token(*token.key)
+      .authority(*owner.key)
+      .payer(*owner.key)
+      .system_program(*system_program.key)
+      .sysvar_instructions(*self.sysvar_instructions.key)
+      .spl_token_program(*spl_token_program.key);
+    if let Some(rule_set) = self.rule_set {
+      builder
+        .authorization_rules_program(*self.authorization_rules_program.key)
+        .authorization_rules(rule_set);
+    }
+    let instruction = builder
+      .build(RevokeArgs::SaleV1)
+      .map_err(|_| ProgramError::InvalidArgument)?
+      .instruction();
+
+    invoke(&instruction, &[
+      delegate.clone(),
+      self.metadata.clone(),
+      self.edition.clone(),
+      self.token_record.clone(),
+      mint.clone(),
+      token.clone(),
+      owner.clone(),
+      system_program.clone(),
+      self.sysvar_instructions.clone(),
+      spl_token_program.clone(),
+      self.authorization_rules_program.clone(),
+      self.authorization_rules.clone(),
+      self.token_metadata_program.clone(),
+    ])?;
+
+    Ok(())
+  }
+
+  /// Transfer the NFT with Token Metadata `TransferV1`, signed by the sale delegate.
+  pub fn transfer(
+    &self,
+    authority: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    token: &AccountInfo<'info>,
+    token_owner: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    destination_owner: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    spl_token_program: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+  ) -> Result<()> {
+    let destination_token_record = self.destination_token_record.as_ref().ok_or(ErrorCode::ProgrammableAccountsMissing)?;
+    let spl_ata_program = self.spl_ata_program.as_ref().ok_or(ErrorCode::ProgrammableAccountsMissing)?;
+    require!(
+      *destination_token_record.key == find_token_record_account(mint.key, destination.key).0,
+      ErrorCode::TokenRecordAccountNotCorrect
+    );
+
+    let mut builder = TransferBuilder::new();
+    builder
+      .token(*token.key)
+      .token_owner(*token_owner.key)
+      .destination(*destination.key)
+      .destination_owner(*destination_owner.key)
+      .mint(*mint.key)
+      .metadata(*self.metadata.key)
+      .edition(*self.edition.key)
+      .owner_token_record(*self.token_record.key)
+      .destination_token_record(*destination_token_record.key)
+      .authority(*authority.key)
+      .payer(*payer.key)
+      .system_program(*system_program.key)
+      .sysvar_instructions(*self.sysvar_instructions.key)
+      .spl_token_program(*spl_token_program.key)
+      .spl_ata_program(*spl_ata_program.key);
+    if let Some(rule_set) = self.rule_set {
+      builder
+        .authorization_rules_program(*self.authorization_rules_program.key)
+        .authorization_rules(rule_set);
+    }
+    let instruction = builder
+      .build(TransferArgs::V1 {
+        amount: 1,
+        authorization_data: None,
+      })
+      .map_err(|_| ProgramError::InvalidArgument)?
+      .instruction();
+
+    invoke_signed(&instruction, &[
+      token.clone(),
+      token_owner.clone(),
+      destination.clone(),
+      destination_owner.clone(),
+      mint.clone(),
+      self.metadata.clone(),
+      self.edition.clone(),
+      self.token_record.clone(),
+      destination_token_record.clone(),
+      authority.clone(),
+      payer.clone(),
+      system_program.clone(),
+      self.sysvar_instructions.clone(),
+      spl_token_program.clone(),
+      spl_ata_program.clone(),
+      self.authorization_rules_program.clone(),
+      self.authorization_rules.clone(),
+      self.token_metadata_program.clone(),
+    ], signer_seeds)?;
+
+    Ok(())
+  }
+}
+
+/// Royalty of an NFT, split between `recipients` by their share in percent
+pub struct Royalty {
+  pub seller_fee_basis_points: u16,
+  pub recipients: Vec<(Pubkey, u8)>,
+}
+
+impl Royalty {
+
+  /// Read the royalty and creators from the Token Metadata account of `mint`.
+  pub fn load(metadata: &AccountInfo, mint: &Pubkey) -> Result<Self> {
+    require!(*metadata.key == find_metadata_account(mint).0, ErrorCode::MetadataAccountNotCorrect);
+
+    let metadata_data = Metadata::from_account_info(metadata)?;
+    let recipients = metadata_data.data.creators
+      .unwrap_or_default()
+      .iter()
+      .filter(|creator| creator.share > 0)
+      .map(|creator| (creator.address, creator.share))
+      .collect();
+
+    Ok(Royalty {
+      seller_fee_basis_points: metadata_data.data.seller_fee_basis_points,
+      recipients,
+    })
+  }
+
+  /// Scale the royalty down to `share_basis_points` of the full royalty
+  pub fn apply_share(&mut self, share_basis_points: u16) {
+    self.seller_fee_basis_points = (self.seller_fee_basis_points as u32 * share_basis_points.min(10000) as u32 / 10000) as u16;
+  }
+
+  /// Royalty owed to each recipient for a sale at `price`, rounding dust to the first recipient
+  pub fn get_amounts(&self, price: u64) -> Vec<u64> {
+    if self.recipients.is_empty() {
+      return vec![];
+    }
+    let royalty = (price as u128 * self.seller_fee_basis_points as u128 / 10000) as u64;
+    let mut amounts: Vec<u64> = self.recipients
+      .iter()
+      .map(|(_, share)| (royalty as u128 * *share as u128 / 100) as u64)
+      .collect();
+    let dust = royalty - amounts.iter().sum::<u64>();
+    amounts[0] += dust;
+    amounts
+  }
+}
+
+/// Checks if `metadata` is the Token Metadata account of `mint` and `mint` is a verified member of `collection`
+pub fn is_verified_collection_member(metadata: &AccountInfo, mint: &Pubkey, collection: &Pubkey) -> Result<bool> {
+  Ok(get_verified_collection(metadata, mint)? == Some(*collection))
+}
+
+/// Verified collection of `mint` read from its Token Metadata account `metadata`, None if not in one
+pub fn get_verified_collection(metadata: &AccountInfo, mint: &Pubkey) -> Result<Option<Pubkey>> {
+  require!(*metadata.key == find_metadata_account(mint).0, ErrorCode::MetadataAccountNotCorrect);
+
+  let metadata_data = Metadata::from_account_info(metadata)?;
+  Ok(metadata_data.collection.filter(|member| member.verified).map(|member| member.key))
+}
@@ -9,7 +9,13 @@ use solana_program::{
   program_error::{
     ProgramError,
   },
+  pubkey::{
+    Pubkey,
+  },
   system_instruction::{
+    allocate,
+    assign,
+    create_account as system_create_account,
     transfer,
   },
 };
@@ -22,14 +28,53 @@ pub fn transfer_lamport<'a>(
 ) -> std::result::Result<(), ProgramError> {
 
   let instruction = transfer(
-    &owner.key,
-    &recipient.key,
+    owner.key,
+    recipient.key,
     amount,
   );
-  if signer_seeds.len() == 0 {
+  if signer_seeds.is_empty() {
     invoke(&instruction, &[owner.clone(), recipient.clone()])
   }
   else {
-    invoke_signed(&instruction, &[owner.clone(), recipient.clone()], &signer_seeds)
+    invoke_signed(&instruction, &[owner.clone(), recipient.clone()], signer_seeds)
+  }
+}
+
+/// Create `new_account` with `space` bytes owned by `owner`. An address already holding lamports
+/// cannot be created by the System program, so like Anchor `init` it is topped up to `lamports`,
+/// then allocated and assigned instead.
+pub fn create_account<'a>(
+  payer: &AccountInfo<'a>,
+  new_account: &AccountInfo<'a>,
+  lamports: u64,
+  space: u64,
+  owner: &Pubkey,
+  signer_seeds: &[&[&[u8]]],
+) -> std::result::Result<(), ProgramError> {
+
+  let current_lamports = new_account.lamports();
+  if current_lamports > 0 {
+    let required_lamports = lamports.saturating_sub(current_lamports);
+    if required_lamports > 0 {
+      transfer_lamport(payer, new_account, required_lamports, &[])?;
+    }
+    let allocate_instruction = allocate(new_account.key, space);
+    invoke_signed(&allocate_instruction, std::slice::from_ref(new_account), signer_seeds)?;
+    let assign_instruction = assign(new_account.key, owner);
+    return invoke_signed(&assign_instruction, std::slice::from_ref(new_account), signer_seeds);
+  }
+
+  let instruction = system_create_account(
+    payer.key,
+    new_account.key,
+    lamports,
+    space,
+    owner,
+  );
+  if signer_seeds.is_empty() {
+    invoke(&instruction, &[payer.clone(), new_account.clone()])
+  }
+  else {
+    invoke_signed(&instruction, &[payer.clone(), new_account.clone()], signer_seeds)
   }
 }
@@ -8,6 +8,7 @@ use solana_program::{
     invoke_signed,
   },
   program_pack::{
+    IsInitialized,
     Pack,
   },
 };
@@ -18,12 +19,64 @@ use crate::external::spl_token;
 use crate::external::spl_token::{
   ID as TOKEN_PROGRAM_ID,
 };
+use crate::external::spl_token_2022;
+use crate::external::spl_token_2022::{
+  AccountType,
+};
 
 #[derive(Clone)]
 pub struct TokenAccount(spl_token::TokenAccount);
 
 impl TokenAccount {
   pub const LEN: usize = spl_token::TokenAccount::LEN;
+
+  /// Unpack a token account owned by either SPL Token or Token-2022
+  pub fn try_from_account_info<'a>(info: &AccountInfo<'a>) -> std::result::Result<Self, ProgramError> {
+    let data = info.try_borrow_data()?;
+    unpack_base_state(info.owner, &data, AccountType::Account).map(TokenAccount)
+  }
+}
+
+#[derive(Clone)]
+pub struct Mint(spl_token::Mint);
+
+impl Mint {
+  pub const LEN: usize = spl_token::Mint::LEN;
+
+  /// Unpack a mint owned by either SPL Token or Token-2022
+  pub fn try_from_account_info<'a>(info: &AccountInfo<'a>) -> std::result::Result<Self, ProgramError> {
+    let data = info.try_borrow_data()?;
+    unpack_base_state(info.owner, &data, AccountType::Mint).map(Mint)
+  }
+}
+
+impl Deref for Mint {
+  type Target = spl_token::Mint;
+
+  fn deref(&self) -> &Self::Target {
+      &self.0
+  }
+}
+
+/// Token-2022 keeps the SPL Token layout and appends its extensions after it
+fn unpack_base_state<T: Pack + IsInitialized>(
+  owner: &Pubkey,
+  data: &[u8],
+  account_type: AccountType,
+) -> std::result::Result<T, ProgramError> {
+  if *owner == spl_token::ID {
+    return T::unpack(data);
+  }
+  if *owner != spl_token_2022::ID {
+    return Err(ProgramError::IllegalOwner);
+  }
+  if data.len() > T::LEN {
+    spl_token_2022::get_extensions(data, account_type)?;
+  }
+  if data.len() < T::LEN {
+    return Err(ProgramError::InvalidAccountData);
+  }
+  T::unpack(&data[..T::LEN])
 }
 
 impl anchor_lang::AccountDeserialize for TokenAccount {
@@ -76,10 +129,159 @@ pub fn transfer_token<'a>(
     ],
     data: data.try_to_vec().unwrap(),
   };
-  if signer_seeds.len() == 0 {
+  if signer_seeds.is_empty() {
     invoke(&instruction, &[from_pubkey.clone(), to_pubkey.clone(), owner.clone()])
   }
   else {
-    invoke_signed(&instruction, &[from_pubkey.clone(), to_pubkey.clone(), owner.clone()], &signer_seeds)
+    invoke_signed(&instruction, &[from_pubkey.clone(), to_pubkey.clone(), owner.clone()], signer_seeds)
+  }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct ApproveTokenParams {
+  pub instruction: u8,
+  pub amount: u64,
+}
+
+/// Approve `delegate` to move `amount` tokens out of `source`, for either SPL Token or Token-2022
+pub fn approve_token<'a>(
+  token_program: &AccountInfo<'a>,
+  owner: &AccountInfo<'a>,
+  source: &AccountInfo<'a>,
+  delegate: &AccountInfo<'a>,
+  amount: u64,
+) -> std::result::Result<(), ProgramError> {
+  let data = ApproveTokenParams {
+    instruction: 4,
+    amount,
+  };
+  let instruction = Instruction {
+    program_id: *token_program.key,
+    accounts: vec![
+      AccountMeta::new(*source.key, false),
+      AccountMeta::new_readonly(*delegate.key, false),
+      AccountMeta::new_readonly(*owner.key, true),
+    ],
+    data: data.try_to_vec().unwrap(),
+  };
+  invoke(&instruction, &[source.clone(), delegate.clone(), owner.clone(), token_program.clone()])
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct RevokeTokenParams {
+  pub instruction: u8,
+}
+
+/// Revoke the delegate of `source`, for either SPL Token or Token-2022
+pub fn revoke_token<'a>(
+  token_program: &AccountInfo<'a>,
+  owner: &AccountInfo<'a>,
+  source: &AccountInfo<'a>,
+) -> std::result::Result<(), ProgramError> {
+  let data = RevokeTokenParams {
+    instruction: 5,
+  };
+  let instruction = Instruction {
+    program_id: *token_program.key,
+    accounts: vec![
+      AccountMeta::new(*source.key, false),
+      AccountMeta::new_readonly(*owner.key, true),
+    ],
+    data: data.try_to_vec().unwrap(),
+  };
+  invoke(&instruction, &[source.clone(), owner.clone(), token_program.clone()])
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct CloseTokenAccountParams {
+  pub instruction: u8,
+}
+
+/// Close the empty token account `account` and send its rent to `destination`,
+/// for either SPL Token or Token-2022
+pub fn close_token_account<'a>(
+  token_program: &AccountInfo<'a>,
+  owner: &AccountInfo<'a>,
+  account: &AccountInfo<'a>,
+  destination: &AccountInfo<'a>,
+  signer_seeds: &[&[&[u8]]],
+) -> std::result::Result<(), ProgramError> {
+  let data = CloseTokenAccountParams {
+    instruction: 9,
+  };
+  let instruction = Instruction {
+    program_id: *token_program.key,
+    accounts: vec![
+      AccountMeta::new(*account.key, false),
+      AccountMeta::new(*destination.key, false),
+      AccountMeta::new_readonly(*owner.key, true),
+    ],
+    data: data.try_to_vec().unwrap(),
+  };
+  let account_infos = [account.clone(), destination.clone(), owner.clone(), token_program.clone()];
+  if signer_seeds.is_empty() {
+    invoke(&instruction, &account_infos)
+  }
+  else {
+    invoke_signed(&instruction, &account_infos, signer_seeds)
+  }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+pub struct TransferCheckedTokenParams {
+  pub instruction: u8,
+  pub amount: u64,
+  pub decimals: u8,
+}
+
+/// Transfer tokens with `TransferChecked`, which both SPL Token and Token-2022 accept.
+/// Token-2022 withholds the transfer fee of the mint, if any, from the amount received.
+/// `additional_accounts` are forwarded to the transfer hook program of the mint, if any.
+pub fn transfer_token_checked<'a>(
+  token_program: &AccountInfo<'a>,
+  owner: &AccountInfo<'a>,
+  from_pubkey: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  to_pubkey: &AccountInfo<'a>,
+  amount: u64,
+  decimals: u8,
+  additional_accounts: &[AccountInfo<'a>],
+  signer_seeds: &[&[&[u8]]],
+) -> std::result::Result<(), ProgramError> {
+  let data = TransferCheckedTokenParams {
+    instruction: 12,
+    amount,
+    decimals,
+  };
+  let mut instruction = Instruction {
+    program_id: *token_program.key,
+    accounts: vec![
+      AccountMeta::new(*from_pubkey.key, false),
+      AccountMeta::new_readonly(*mint.key, false),
+      AccountMeta::new(*to_pubkey.key, false),
+      AccountMeta::new_readonly(*owner.key, true),
+    ],
+    data: data.try_to_vec().unwrap(),
+  };
+  let mut account_infos = vec![
+    from_pubkey.clone(),
+    mint.clone(),
+    to_pubkey.clone(),
+    owner.clone(),
+    token_program.clone(),
+  ];
+  for account in additional_accounts {
+    instruction.accounts.push(if account.is_writable {
+      AccountMeta::new(*account.key, false)
+    } else {
+      AccountMeta::new_readonly(*account.key, false)
+    });
+    account_infos.push(account.clone());
+  }
+  if signer_seeds.is_empty() {
+    invoke(&instruction, &account_infos)
+  }
+  else {
+    invoke_signed(&instruction, &account_infos, signer_seeds)
   }
 }
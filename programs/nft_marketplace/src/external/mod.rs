@@ -1,3 +1,6 @@
+pub mod anchor_mpl_token_metadata;
 pub mod anchor_spl_system;
 pub mod anchor_spl_token;
+pub mod price_feed;
 pub mod spl_token;
+pub mod spl_token_2022;
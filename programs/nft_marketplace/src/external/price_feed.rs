@@ -0,0 +1,143 @@
+use arrayref::{
+  array_ref,
+  array_refs,
+};
+use solana_program::{
+  account_info::{
+    AccountInfo,
+  },
+  program_error::{
+    ProgramError,
+  },
+};
+
+pub const PRICE_FEED_MAGIC: u32 = 0x4653_4450;
+
+/// Decimals of the USD prices stored in listings
+pub const USD_PRICE_DECIMALS: u32 = 6;
+
+/// Price feed account read by USD-pegged listings, with the following layout
+/// (little endian, 32 bytes):
+///
+///   0. `[0..4]`   magic, `PRICE_FEED_MAGIC`
+///   1. `[4..12]`  price, `i64`, USD price of one whole currency token is `price * 10^exponent`
+///   2. `[12..20]` confidence, `u64`, confidence interval of the price, same scale as `price`
+///   3. `[20..24]` exponent, `i32`
+///   4. `[24..32]` publish time, `i64`, unix timestamp of the last update
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PriceFeed {
+  pub price: i64,
+  pub confidence: u64,
+  pub exponent: i32,
+  pub publish_time: i64,
+}
+
+impl PriceFeed {
+  pub const LEN: usize = 32;
+
+  pub fn unpack(src: &[u8]) -> Result<PriceFeed, ProgramError> {
+    if src.len() < Self::LEN {
+      return Err(ProgramError::InvalidAccountData);
+    }
+    let src = array_ref![src, 0, 32];
+    let (magic, price, confidence, exponent, publish_time) = array_refs![src, 4, 8, 8, 4, 8];
+    if u32::from_le_bytes(*magic) != PRICE_FEED_MAGIC {
+      return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(PriceFeed {
+      price: i64::from_le_bytes(*price),
+      confidence: u64::from_le_bytes(*confidence),
+      exponent: i32::from_le_bytes(*exponent),
+      publish_time: i64::from_le_bytes(*publish_time),
+    })
+  }
+
+  pub fn from_account_info<'a>(info: &AccountInfo<'a>) -> Result<PriceFeed, ProgramError> {
+    PriceFeed::unpack(&info.try_borrow_data()?)
+  }
+
+  /// Checks if the price was published within `max_age` seconds of `now`
+  pub fn is_fresh(&self, now: i64, max_age: i64) -> bool {
+    self.publish_time <= now && now - self.publish_time <= max_age
+  }
+
+  /// Checks if the confidence interval is within `max_basis_points` of the price
+  pub fn is_confident(&self, max_basis_points: u64) -> bool {
+    self.price > 0
+      && (self.confidence as u128) * 10_000 <= (self.price as u128) * (max_basis_points as u128)
+  }
+
+  /// Convert a USD price with `USD_PRICE_DECIMALS` into base units of a currency with `decimals`,
+  /// rounded up so the seller never receives less than the USD price
+  pub fn get_settlement_amount(&self, usd_price: u64, decimals: u8) -> Option<u64> {
+    if self.price <= 0 {
+      return None;
+    }
+    let mut numerator = (usd_price as u128).checked_mul(10u128.checked_pow(decimals as u32)?)?;
+    let mut denominator = (self.price as u128).checked_mul(10u128.pow(USD_PRICE_DECIMALS))?;
+    if self.exponent < 0 {
+      numerator = numerator.checked_mul(10u128.checked_pow(self.exponent.unsigned_abs())?)?;
+    } else {
+      denominator = denominator.checked_mul(10u128.checked_pow(self.exponent as u32)?)?;
+    }
+    let amount = numerator.checked_add(denominator - 1)?.checked_div(denominator)?;
+    u64::try_from(amount).ok()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn price_feed(price: i64, exponent: i32) -> PriceFeed {
+    PriceFeed {
+      price,
+      confidence: 0,
+      exponent,
+      publish_time: 0,
+    }
+  }
+
+  #[test]
+  fn get_settlement_amount_with_negative_exponent() {
+    // 1 token = 150 USD
+    let feed = price_feed(150_000_000, -6);
+    assert_eq!(feed.get_settlement_amount(300_000_000, 9), Some(2_000_000_000));
+    assert_eq!(feed.get_settlement_amount(150_000_000, 0), Some(1));
+  }
+
+  #[test]
+  fn get_settlement_amount_with_positive_exponent() {
+    // 1 token = 200 USD
+    let feed = price_feed(2, 2);
+    assert_eq!(feed.get_settlement_amount(200_000_000, 0), Some(1));
+    assert_eq!(feed.get_settlement_amount(100_000_000, 6), Some(500_000));
+  }
+
+  #[test]
+  fn get_settlement_amount_rounds_up() {
+    let feed = price_feed(150_000_000, -6);
+    assert_eq!(feed.get_settlement_amount(1, 9), Some(7));
+    assert_eq!(feed.get_settlement_amount(1, 0), Some(1));
+  }
+
+  #[test]
+  fn get_settlement_amount_rejects_bad_prices() {
+    assert_eq!(price_feed(0, -6).get_settlement_amount(1_000_000, 9), None);
+    assert_eq!(price_feed(-1, -6).get_settlement_amount(1_000_000, 9), None);
+    assert_eq!(price_feed(1, -6).get_settlement_amount(1_000_000, 39), None);
+    assert_eq!(price_feed(1, -30).get_settlement_amount(u64::MAX, 18), None);
+  }
+
+  #[test]
+  fn unpack_checks_magic() {
+    let mut data = [0u8; 32];
+    data[0..4].copy_from_slice(&PRICE_FEED_MAGIC.to_le_bytes());
+    data[4..12].copy_from_slice(&150_000_000i64.to_le_bytes());
+    data[20..24].copy_from_slice(&(-6i32).to_le_bytes());
+    assert_eq!(PriceFeed::unpack(&data), Ok(price_feed(150_000_000, -6)));
+    data[0] ^= 1;
+    assert_eq!(PriceFeed::unpack(&data), Err(ProgramError::InvalidAccountData));
+    assert_eq!(PriceFeed::unpack(&data[..31]), Err(ProgramError::InvalidAccountData));
+  }
+}
@@ -122,11 +122,79 @@ impl Pack for TokenAccount {
   }
 }
 
+/// Mint data.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Mint {
+  /// Optional authority used to mint new tokens. The mint authority may only be provided during
+  /// mint creation. If no mint authority is present then the mint has a fixed supply and no
+  /// further tokens may be minted.
+  pub mint_authority: COption<Pubkey>,
+  /// Total supply of tokens.
+  pub supply: u64,
+  /// Number of base 10 digits to the right of the decimal place.
+  pub decimals: u8,
+  /// Is `true` if this structure has been initialized
+  pub is_initialized: bool,
+  /// Optional authority to freeze token accounts.
+  pub freeze_authority: COption<Pubkey>,
+}
+
+impl Sealed for Mint {}
+impl IsInitialized for Mint {
+  fn is_initialized(&self) -> bool {
+    self.is_initialized
+  }
+}
+impl Pack for Mint {
+  const LEN: usize = 82;
+  fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+    let src = array_ref![src, 0, 82];
+    let (mint_authority, supply, decimals, is_initialized, freeze_authority) =
+      array_refs![src, 36, 8, 1, 1, 36];
+    let is_initialized = match is_initialized {
+      [0] => false,
+      [1] => true,
+      _ => return Err(ProgramError::InvalidAccountData),
+    };
+    Ok(Mint {
+      mint_authority: unpack_coption_key(mint_authority)?,
+      supply: u64::from_le_bytes(*supply),
+      decimals: decimals[0],
+      is_initialized,
+      freeze_authority: unpack_coption_key(freeze_authority)?,
+    })
+  }
+  fn pack_into_slice(&self, dst: &mut [u8]) {
+    let dst = array_mut_ref![dst, 0, 82];
+    let (
+      mint_authority_dst,
+      supply_dst,
+      decimals_dst,
+      is_initialized_dst,
+      freeze_authority_dst,
+    ) = mut_array_refs![dst, 36, 8, 1, 1, 36];
+    let &Mint {
+      ref mint_authority,
+      supply,
+      decimals,
+      is_initialized,
+      ref freeze_authority,
+    } = self;
+    pack_coption_key(mint_authority, mint_authority_dst);
+    *supply_dst = supply.to_le_bytes();
+    decimals_dst[0] = decimals;
+    is_initialized_dst[0] = is_initialized as u8;
+    pack_coption_key(freeze_authority, freeze_authority_dst);
+  }
+}
+
 /// Account state.
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, TryFromPrimitive)]
 pub enum AccountState {
   /// Account is not yet initialized
+  #[default]
   Uninitialized,
   /// Account is initialized; the account owner and/or delegate may perform permitted operations
   /// on this account
@@ -136,11 +204,6 @@ pub enum AccountState {
   Frozen,
 }
 
-impl Default for AccountState {
-  fn default() -> Self {
-    AccountState::Uninitialized
-  }
-}
 
 pub fn is_token_program<'a>(account: &AccountInfo<'a>) -> bool {
   *account.key == ID
@@ -0,0 +1,427 @@
+use arrayref::{
+  array_ref,
+  array_refs,
+};
+use num_enum::{
+  TryFromPrimitive,
+};
+use solana_program::{
+  account_info::{
+    AccountInfo,
+  },
+  declare_id,
+  program_error::{
+    ProgramError,
+  },
+  program_pack::{
+    Pack,
+  },
+  pubkey::{
+    Pubkey,
+  },
+};
+use anchor_lang::{
+  prelude::borsh,
+  AnchorDeserialize,
+};
+use crate::external::spl_token;
+
+declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Extensions are stored after the base state, padded to the size of a token account.
+const ACCOUNT_TYPE_OFFSET: usize = spl_token::TokenAccount::LEN;
+
+/// Type of account stored before the extensions.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+pub enum AccountType {
+  /// Marker for 0 data
+  Uninitialized,
+  /// Mint account with additional extensions
+  Mint,
+  /// Token holding account with additional extensions
+  Account,
+}
+
+/// Extensions that can be applied to mints or accounts.
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, TryFromPrimitive)]
+pub enum ExtensionType {
+  /// Used as padding if the account size would otherwise be 355, same as a multisig
+  Uninitialized,
+  /// Includes transfer fee rate info and accompanying authorities to withdraw and set the fee
+  TransferFeeConfig,
+  /// Includes withheld transfer fees
+  TransferFeeAmount,
+  /// Includes an optional mint close authority
+  MintCloseAuthority,
+  /// Auditor configuration for confidential transfers
+  ConfidentialTransferMint,
+  /// State for confidential transfers
+  ConfidentialTransferAccount,
+  /// Specifies the default Account::state for new Accounts
+  DefaultAccountState,
+  /// Indicates that the Account owner authority cannot be changed
+  ImmutableOwner,
+  /// Require inbound transfers to have memo
+  MemoTransfer,
+  /// Indicates that the tokens from this mint can't be transferred
+  NonTransferable,
+  /// Tokens accrue interest over time,
+  InterestBearingConfig,
+  /// Locks privileged token operations from happening via CPI
+  CpiGuard,
+  /// Includes an optional permanent delegate
+  PermanentDelegate,
+  /// Indicates that the tokens in this account belong to a non-transferable mint
+  NonTransferableAccount,
+  /// Mint requires a CPI to a program implementing the "transfer hook" interface
+  TransferHook,
+  /// Indicates that the tokens in this account belong to a mint with a transfer hook
+  TransferHookAccount,
+  /// Includes encrypted withheld fees and the encryption public that they are encrypted under
+  ConfidentialTransferFeeConfig,
+  /// Includes confidential withheld transfer fees
+  ConfidentialTransferFeeAmount,
+  /// Mint contains a pointer to another account (or the same account) that holds metadata
+  MetadataPointer,
+  /// Mint contains token-metadata
+  TokenMetadata,
+  /// Mint contains a pointer to another account (or the same account) that holds group configurations
+  GroupPointer,
+  /// Mint contains token group configurations
+  TokenGroup,
+  /// Mint contains a pointer to another account (or the same account) that holds group member configurations
+  GroupMemberPointer,
+  /// Mint contains token group member configurations
+  TokenGroupMember,
+}
+
+impl ExtensionType {
+  /// Checks if a mint with this extension can be used as a marketplace currency
+  pub fn is_supported_currency_extension(&self) -> bool {
+    matches!(
+      self,
+      ExtensionType::TransferFeeConfig
+        | ExtensionType::MintCloseAuthority
+        | ExtensionType::DefaultAccountState
+        | ExtensionType::InterestBearingConfig
+        | ExtensionType::MetadataPointer
+        | ExtensionType::TokenMetadata
+        | ExtensionType::GroupPointer
+        | ExtensionType::GroupMemberPointer
+        | ExtensionType::TokenGroupMember
+    )
+  }
+}
+
+/// Transfer fee information
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransferFee {
+  /// First epoch where the transfer fee takes effect
+  pub epoch: u64,
+  /// Maximum fee assessed on transfers, expressed as an amount of tokens
+  pub maximum_fee: u64,
+  /// Amount of transfer collected as fees, expressed as basis points of the
+  /// transfer amount, ie. increments of 0.01%
+  pub transfer_fee_basis_points: u16,
+}
+
+impl TransferFee {
+  pub const LEN: usize = 18;
+
+  /// Calculate the fee withheld by the mint when `amount` is transferred
+  pub fn calculate_fee(&self, amount: u64) -> Option<u64> {
+    let transfer_fee_basis_points = self.transfer_fee_basis_points as u128;
+    if transfer_fee_basis_points == 0 || amount == 0 {
+      return Some(0);
+    }
+    let numerator = (amount as u128).checked_mul(transfer_fee_basis_points)?;
+    let raw_fee = numerator.checked_add(9_999)?.checked_div(10_000)?;
+    Some(u64::try_from(raw_fee).ok()?.min(self.maximum_fee))
+  }
+
+  fn unpack(src: &[u8; 18]) -> TransferFee {
+    let (epoch, maximum_fee, transfer_fee_basis_points) = array_refs![src, 8, 8, 2];
+    TransferFee {
+      epoch: u64::from_le_bytes(*epoch),
+      maximum_fee: u64::from_le_bytes(*maximum_fee),
+      transfer_fee_basis_points: u16::from_le_bytes(*transfer_fee_basis_points),
+    }
+  }
+}
+
+/// Transfer fee extension data for mints.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransferFeeConfig {
+  /// Optional authority to set the fee
+  pub transfer_fee_config_authority: Pubkey,
+  /// Withdraw from mint instructions must be signed by this key
+  pub withdraw_withheld_authority: Pubkey,
+  /// Withheld transfer fee tokens that have been moved to the mint for withdrawal
+  pub withheld_amount: u64,
+  /// Older transfer fee, used if the current epoch < new_transfer_fee.epoch
+  pub older_transfer_fee: TransferFee,
+  /// Newer transfer fee, used if the current epoch >= new_transfer_fee.epoch
+  pub newer_transfer_fee: TransferFee,
+}
+
+impl TransferFeeConfig {
+  pub const LEN: usize = 108;
+
+  /// Get the fee for the given epoch
+  pub fn get_epoch_fee(&self, epoch: u64) -> &TransferFee {
+    if epoch >= self.newer_transfer_fee.epoch {
+      &self.newer_transfer_fee
+    } else {
+      &self.older_transfer_fee
+    }
+  }
+
+  fn unpack(src: &[u8]) -> Result<TransferFeeConfig, ProgramError> {
+    if src.len() != Self::LEN {
+      return Err(ProgramError::InvalidAccountData);
+    }
+    let src = array_ref![src, 0, 108];
+    let (
+      transfer_fee_config_authority,
+      withdraw_withheld_authority,
+      withheld_amount,
+      older_transfer_fee,
+      newer_transfer_fee,
+    ) = array_refs![src, 32, 32, 8, 18, 18];
+    Ok(TransferFeeConfig {
+      transfer_fee_config_authority: Pubkey::new_from_array(*transfer_fee_config_authority),
+      withdraw_withheld_authority: Pubkey::new_from_array(*withdraw_withheld_authority),
+      withheld_amount: u64::from_le_bytes(*withheld_amount),
+      older_transfer_fee: TransferFee::unpack(older_transfer_fee),
+      newer_transfer_fee: TransferFee::unpack(newer_transfer_fee),
+    })
+  }
+}
+
+/// Metadata pointer extension data for mints.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MetadataPointer {
+  /// Authority that can set the metadata address
+  pub authority: Pubkey,
+  /// Account address that holds the metadata
+  pub metadata_address: Pubkey,
+}
+
+impl MetadataPointer {
+  pub const LEN: usize = 64;
+
+  fn unpack(src: &[u8]) -> Result<MetadataPointer, ProgramError> {
+    if src.len() != Self::LEN {
+      return Err(ProgramError::InvalidAccountData);
+    }
+    let (authority, metadata_address) = array_refs![array_ref![src, 0, 64], 32, 32];
+    Ok(MetadataPointer {
+      authority: Pubkey::new_from_array(*authority),
+      metadata_address: Pubkey::new_from_array(*metadata_address),
+    })
+  }
+}
+
+/// Transfer hook extension data for mints.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransferHook {
+  /// Authority that can set the transfer hook program id
+  pub authority: Pubkey,
+  /// Program that authorizes the transfer, `Pubkey::default()` if unset
+  pub program_id: Pubkey,
+}
+
+impl TransferHook {
+  pub const LEN: usize = 64;
+
+  fn unpack(src: &[u8]) -> Result<TransferHook, ProgramError> {
+    if src.len() != Self::LEN {
+      return Err(ProgramError::InvalidAccountData);
+    }
+    let (authority, program_id) = array_refs![array_ref![src, 0, 64], 32, 32];
+    Ok(TransferHook {
+      authority: Pubkey::new_from_array(*authority),
+      program_id: Pubkey::new_from_array(*program_id),
+    })
+  }
+}
+
+/// Token metadata extension data for mints, borsh encoded.
+#[derive(AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct TokenMetadata {
+  /// The authority that can sign to update the metadata
+  pub update_authority: Pubkey,
+  /// The associated mint, used to counter spoofing to be sure that metadata
+  /// belongs to a particular mint
+  pub mint: Pubkey,
+  /// The longer name of the token
+  pub name: String,
+  /// The shortened symbol for the token
+  pub symbol: String,
+  /// The URI pointing to richer metadata
+  pub uri: String,
+  /// Any additional metadata about the token as key-value pairs
+  pub additional_metadata: Vec<(String, String)>,
+}
+
+impl TokenMetadata {
+  /// Look up a value of the additional metadata
+  pub fn get_additional_metadata(&self, key: &str) -> Option<&str> {
+    self.additional_metadata
+      .iter()
+      .find(|(field, _)| field == key)
+      .map(|(_, value)| value.as_str())
+  }
+}
+
+pub fn is_token_2022_program<'a>(account: &AccountInfo<'a>) -> bool {
+  *account.key == ID
+}
+
+/// Checks if the program owning a mint or token account is SPL Token or Token-2022
+pub fn is_supported_token_program(program_id: &Pubkey) -> bool {
+  *program_id == spl_token::ID || *program_id == ID
+}
+
+/// Walk the extensions stored after the base state of a mint or token account
+pub fn get_extensions(
+  data: &[u8],
+  account_type: AccountType,
+) -> Result<Vec<(ExtensionType, &[u8])>, ProgramError> {
+  let mut extensions = Vec::new();
+  if data.len() <= ACCOUNT_TYPE_OFFSET {
+    return Ok(extensions);
+  }
+  if AccountType::try_from_primitive(data[ACCOUNT_TYPE_OFFSET]) != Ok(account_type) {
+    return Err(ProgramError::InvalidAccountData);
+  }
+
+  let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+  while offset + 4 <= data.len() {
+    let (extension_type, length) = array_refs![array_ref![data, offset, 4], 2, 2];
+    let extension_type = ExtensionType::try_from_primitive(u16::from_le_bytes(*extension_type))
+      .or(Err(ProgramError::InvalidAccountData))?;
+    if extension_type == ExtensionType::Uninitialized {
+      break;
+    }
+    let start = offset + 4;
+    let end = start + u16::from_le_bytes(*length) as usize;
+    if end > data.len() {
+      return Err(ProgramError::InvalidAccountData);
+    }
+    extensions.push((extension_type, &data[start..end]));
+    offset = end;
+  }
+  Ok(extensions)
+}
+
+/// Checks if every extension of a mint can be used for a marketplace currency
+pub fn is_supported_currency_mint(mint_data: &[u8]) -> Result<bool, ProgramError> {
+  Ok(
+    get_extensions(mint_data, AccountType::Mint)?
+      .iter()
+      .all(|(extension_type, _)| extension_type.is_supported_currency_extension())
+  )
+}
+
+/// Read the transfer fee configuration of a mint, if any
+pub fn get_transfer_fee_config(mint_data: &[u8]) -> Result<Option<TransferFeeConfig>, ProgramError> {
+  for (extension_type, value) in get_extensions(mint_data, AccountType::Mint)? {
+    if extension_type == ExtensionType::TransferFeeConfig {
+      return TransferFeeConfig::unpack(value).map(Some);
+    }
+  }
+  Ok(None)
+}
+
+/// Calculate the fee withheld by the mint when `amount` is transferred during `epoch`.
+/// Always zero for SPL Token mints.
+pub fn calculate_transfer_fee<'a>(
+  mint: &AccountInfo<'a>,
+  amount: u64,
+  epoch: u64,
+) -> Result<u64, ProgramError> {
+  if *mint.owner != ID {
+    return Ok(0);
+  }
+  let data = mint.try_borrow_data()?;
+  match get_transfer_fee_config(&data)? {
+    Some(transfer_fee_config) => transfer_fee_config
+      .get_epoch_fee(epoch)
+      .calculate_fee(amount)
+      .ok_or(ProgramError::InvalidArgument),
+    None => Ok(0),
+  }
+}
+
+/// Read the metadata pointer of a mint, if any
+pub fn get_metadata_pointer(mint_data: &[u8]) -> Result<Option<MetadataPointer>, ProgramError> {
+  for (extension_type, value) in get_extensions(mint_data, AccountType::Mint)? {
+    if extension_type == ExtensionType::MetadataPointer {
+      return MetadataPointer::unpack(value).map(Some);
+    }
+  }
+  Ok(None)
+}
+
+/// Read the token metadata stored in a mint, if any
+pub fn get_token_metadata(mint_data: &[u8]) -> Result<Option<TokenMetadata>, ProgramError> {
+  for (extension_type, mut value) in get_extensions(mint_data, AccountType::Mint)? {
+    if extension_type == ExtensionType::TokenMetadata {
+      return TokenMetadata::deserialize(&mut value)
+        .map(Some)
+        .or(Err(ProgramError::InvalidAccountData));
+    }
+  }
+  Ok(None)
+}
+
+/// Read the transfer hook program of a mint, if any
+pub fn get_transfer_hook_program_id(mint_data: &[u8]) -> Result<Option<Pubkey>, ProgramError> {
+  for (extension_type, value) in get_extensions(mint_data, AccountType::Mint)? {
+    if extension_type == ExtensionType::TransferHook {
+      let transfer_hook = TransferHook::unpack(value)?;
+      if transfer_hook.program_id != Pubkey::default() {
+        return Ok(Some(transfer_hook.program_id));
+      }
+    }
+  }
+  Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn transfer_fee(transfer_fee_basis_points: u16, maximum_fee: u64) -> TransferFee {
+    TransferFee {
+      epoch: 0,
+      maximum_fee,
+      transfer_fee_basis_points,
+    }
+  }
+
+  #[test]
+  fn calculate_fee_rounds_up() {
+    let fee = transfer_fee(100, u64::MAX);
+    assert_eq!(fee.calculate_fee(10_000), Some(100));
+    assert_eq!(fee.calculate_fee(10_001), Some(101));
+    assert_eq!(fee.calculate_fee(1), Some(1));
+  }
+
+  #[test]
+  fn calculate_fee_is_capped_by_maximum_fee() {
+    let fee = transfer_fee(5_000, 250);
+    assert_eq!(fee.calculate_fee(400), Some(200));
+    assert_eq!(fee.calculate_fee(1_000), Some(250));
+    assert_eq!(fee.calculate_fee(u64::MAX), Some(250));
+  }
+
+  #[test]
+  fn calculate_fee_without_fee() {
+    assert_eq!(transfer_fee(0, 250).calculate_fee(1_000), Some(0));
+    assert_eq!(transfer_fee(100, 250).calculate_fee(0), Some(0));
+  }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::result_large_err, clippy::too_many_arguments)]
+
 pub mod constant;
 pub mod context;
 pub mod error;
@@ -6,20 +8,43 @@ pub mod state;
 pub mod external;
 
 use anchor_lang::{
+  Discriminator,
   prelude::*,
   solana_program::{
     program::{
         invoke,
         invoke_signed
     },
+    program_option::COption,
     sysvar::rent::Rent,
     instruction:: {
         Instruction,
     }
   },
 };
+use constant::*;
 use context::*;
 use error::ErrorCode;
+use event::*;
+use state::{
+    AcceptedCurrency,
+    BundleItem,
+    ClaimableBalance,
+    CollectionStats,
+    FeeQuote,
+    FeeSplit,
+    FeeTier,
+    FeeTierAsset,
+    MarketplaceNFT,
+    Multisig,
+    PriceObservations,
+    ProposalAccount,
+    RoyaltyPolicy,
+    SellerTradeState,
+    Swap,
+    SwapAsset,
+    TokenGate,
+};
 
 use mpl_token_metadata::{
   instruction as mpl_instruction,
@@ -32,25 +57,768 @@ use mpl_token_metadata::{
 };
 
 use crate::{
-    external::spl_token::{
-    ID as TOKEN_PROGRAM_ID,
-  },
+    external::anchor_spl_system::{
+        create_account,
+        transfer_lamport,
+    },
     external::anchor_spl_token::{
+        approve_token,
+        close_token_account,
+        revoke_token,
         transfer_token,
+        transfer_token_checked,
+        Mint,
+        TokenAccount,
+    },
+    external::spl_token_2022::{
+        calculate_transfer_fee,
+        get_extensions,
+        get_metadata_pointer,
+        get_token_metadata,
+        get_transfer_hook_program_id,
+        is_supported_currency_mint,
+        AccountType,
+        ExtensionType,
+        ID as TOKEN_2022_PROGRAM_ID,
+    },
+    external::anchor_mpl_token_metadata::{
+        get_verified_collection,
+        is_verified_collection_member,
+        ProgrammableNftAccounts,
+        Royalty,
+    },
+    external::price_feed::{
+        PriceFeed,
     },
 };
 declare_id!("H4Theeu9v5WwLSSUc9BTtCehCgw2ap6KxekeQkcbgBJz");
 
 #[derive(AnchorSerialize, AnchorDeserialize, Default)]
-pub struct ApproveTokenParams {
+pub struct TransferTokenParams {
     pub instruction: u8,
     pub amount: u64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Default)]
-pub struct TransferTokenParams {
-    pub instruction: u8,
-    pub amount: u64,
+/// Token-2022 NFTs must be non-divisible, transferable and carry their metadata in the mint itself
+fn check_token_2022_nft(mint_nft_account: &AccountInfo) -> Result<()> {
+    let mint = Mint::try_from_account_info(mint_nft_account)?;
+    require!(mint.decimals == 0 && mint.supply == 1, ErrorCode::NFTMintNotCorrect);
+
+    let mint_data = mint_nft_account.try_borrow_data()?;
+    for (extension_type, _) in get_extensions(&mint_data, AccountType::Mint)? {
+        require!(extension_type != ExtensionType::NonTransferable, ErrorCode::MintExtensionNotSupported);
+    }
+
+    let metadata_pointer = get_metadata_pointer(&mint_data)?.ok_or(ErrorCode::MetadataPointerNotCorrect)?;
+    require!(metadata_pointer.metadata_address == *mint_nft_account.key, ErrorCode::MetadataPointerNotCorrect);
+
+    let token_metadata = get_token_metadata(&mint_data)?.ok_or(ErrorCode::MetadataAccountNotCorrect)?;
+    require!(token_metadata.mint == *mint_nft_account.key, ErrorCode::MetadataAccountNotCorrect);
+
+    Ok(())
+}
+
+/// Token-2022 NFTs pay their royalty to the update authority of the metadata, capped at the full price
+fn get_token_2022_royalty(mint_nft_account: &AccountInfo) -> Result<Royalty> {
+    let mint_data = mint_nft_account.try_borrow_data()?;
+    let token_metadata = get_token_metadata(&mint_data)?.ok_or(ErrorCode::MetadataAccountNotCorrect)?;
+
+    let seller_fee_basis_points = token_metadata
+        .get_additional_metadata(ROYALTY_BASIS_POINTS_KEY)
+        .and_then(|value| value.parse::<u16>().ok())
+        .unwrap_or(0)
+        .min(10000);
+    let recipients = if token_metadata.update_authority == Pubkey::default() {
+        vec![]
+    } else {
+        vec![(token_metadata.update_authority, 100)]
+    };
+
+    Ok(Royalty {
+        seller_fee_basis_points,
+        recipients,
+    })
+}
+
+/// Move every offered asset out of the escrow of `swap` and close the emptied escrow token accounts,
+/// returning their rent to `maker`. `accounts` holds one pair per offered asset:
+/// the escrow token account owned by the swap, then the destination token account.
+fn release_swap_escrow<'info>(
+    swap: &Account<'info, Swap>,
+    maker: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    require!(accounts.len() == swap.offered.len() * SWAP_LEG_ACCOUNTS_LEN, ErrorCode::SwapNotCorrect);
+
+    let swap_id = swap.swap_id.to_le_bytes();
+    let seed : &[&[u8]] = &[
+        SWAP,
+        swap.maker.as_ref(),
+        &swap_id,
+        &[swap.bump]
+    ];
+
+    for (asset, legs) in swap.offered.iter().zip(accounts.chunks(SWAP_LEG_ACCOUNTS_LEN)) {
+        let escrow_token_account = &legs[0];
+        let destination_token_account = &legs[1];
+
+        let escrow = TokenAccount::try_from_account_info(escrow_token_account)?;
+        require!(escrow.owner == swap.key(), ErrorCode::SwapTokenAccountNotCorrect);
+        require!(escrow.mint == asset.mint, ErrorCode::SwapTokenAccountNotCorrect);
+        require!(escrow.amount >= asset.amount, ErrorCode::SwapTokenAccountNotCorrect);
+
+        // Empty the escrow entirely so it can be closed, even if tokens were sent to it on top of the deposit
+        transfer_token(
+            &swap.to_account_info(),
+            escrow_token_account,
+            destination_token_account,
+            escrow.amount,
+            &[seed],
+        )?;
+
+        close_token_account(
+            token_program,
+            &swap.to_account_info(),
+            escrow_token_account,
+            maker,
+            &[seed],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Check that `buyer` meets `token_gate` with the token account, and for collection gates
+/// the Token Metadata account, presented in the sale
+fn check_token_gate(
+    token_gate: &TokenGate,
+    buyer: &Pubkey,
+    gate_token_account: Option<&AccountInfo>,
+    gate_metadata_account: Option<&AccountInfo>,
+) -> Result<()> {
+    let gate_token_account = gate_token_account.ok_or(ErrorCode::TokenGateNotMet)?;
+    let token_account = TokenAccount::try_from_account_info(gate_token_account)?;
+    require!(token_account.owner == *buyer, ErrorCode::TokenGateNotMet);
+
+    match token_gate {
+        TokenGate::Token { mint, min_amount } => {
+            require!(token_account.mint == *mint, ErrorCode::TokenGateNotMet);
+            require!(token_account.amount >= *min_amount, ErrorCode::TokenGateNotMet);
+        }
+        TokenGate::Collection { collection } => {
+            require!(token_account.amount >= 1, ErrorCode::TokenGateNotMet);
+            let gate_metadata_account = gate_metadata_account.ok_or(ErrorCode::TokenGateNotMet)?;
+            require!(
+                is_verified_collection_member(gate_metadata_account, &token_account.mint, collection)?,
+                ErrorCode::TokenGateNotMet
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Holdings of the fee tier asset of the marketplace presented by `owner`, 0 without a token account
+fn get_fee_tier_holdings(
+    fee_tier_asset: &FeeTierAsset,
+    owner: &Pubkey,
+    token_account: Option<&AccountInfo>,
+    metadata_account: Option<&AccountInfo>,
+) -> Result<u64> {
+    let token_account = match token_account {
+        Some(token_account) => TokenAccount::try_from_account_info(token_account)?,
+        None => return Ok(0),
+    };
+    require!(token_account.owner == *owner, ErrorCode::FeeTierAccountNotCorrect);
+
+    match fee_tier_asset {
+        FeeTierAsset::Token { mint } => {
+            require!(token_account.mint == *mint, ErrorCode::FeeTierAccountNotCorrect);
+            Ok(token_account.amount)
+        }
+        FeeTierAsset::Collection { collection } => {
+            let metadata_account = metadata_account.ok_or(ErrorCode::FeeTierAccountNotCorrect)?;
+            require!(
+                token_account.amount >= 1 && is_verified_collection_member(metadata_account, &token_account.mint, collection)?,
+                ErrorCode::FeeTierAccountNotCorrect
+            );
+            Ok(1)
+        }
+    }
+}
+
+/// Index of the fee tier of the marketplace `owner` qualifies for with the holdings presented, None if none
+fn get_fee_tier_index(
+    marketplace: &MarketplaceNFT,
+    owner: &Pubkey,
+    token_account: Option<&AccountInfo>,
+    metadata_account: Option<&AccountInfo>,
+) -> Result<Option<u8>> {
+    let fee_tier_asset = match marketplace.fee_tier_asset.as_ref() {
+        Some(fee_tier_asset) => fee_tier_asset,
+        None => return Ok(None),
+    };
+    let holdings = get_fee_tier_holdings(fee_tier_asset, owner, token_account, metadata_account)?;
+    Ok(marketplace.get_fee_tier(holdings).map(|(index, _)| index))
+}
+
+/// Price feed trusted by `currency`, owned by the price feed program
+fn load_price_feed(price_feed: &AccountInfo, currency: &AcceptedCurrency) -> Result<PriceFeed> {
+    require!(currency.price_feed == Some(*price_feed.key), ErrorCode::PriceFeedNotCorrect);
+    require!(price_feed.owner.to_string() == PRICE_FEED_PROGRAM, ErrorCode::PriceFeedNotCorrect);
+    Ok(PriceFeed::from_account_info(price_feed)?)
+}
+
+/// Royalty of an NFT, from the mint of Token-2022 NFTs or from the Token Metadata account otherwise
+fn load_royalty(mint_nft_account: &AccountInfo, metadata_account: Option<&AccountInfo>) -> Result<Royalty> {
+    if *mint_nft_account.owner == TOKEN_2022_PROGRAM_ID {
+        return get_token_2022_royalty(mint_nft_account);
+    }
+    let metadata_account = metadata_account.ok_or(ErrorCode::MetadataAccountNotCorrect)?;
+    Royalty::load(metadata_account, mint_nft_account.key)
+}
+
+/// Verified collection of an NFT from its Token Metadata account, None for Token-2022 NFTs which have none
+fn load_verified_collection(mint_nft_account: &AccountInfo, metadata_account: Option<&AccountInfo>) -> Result<Option<Pubkey>> {
+    if *mint_nft_account.owner == TOKEN_2022_PROGRAM_ID {
+        return Ok(None);
+    }
+    let metadata_account = metadata_account.ok_or(ErrorCode::MetadataAccountNotCorrect)?;
+    get_verified_collection(metadata_account, mint_nft_account.key)
+}
+
+/// Stats of `collection` for sales in `token_mint_account` on the marketplace, None if the collection has none.
+/// `collection_stats` must be the stats address, so the stats of a collection cannot be left out.
+fn load_collection_stats<'info>(
+    collection_stats: Option<&AccountInfo<'info>>,
+    nft_marketplace_account: &Pubkey,
+    collection: &Pubkey,
+    token_mint_account: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Option<Account<'info, CollectionStats>>> {
+    let collection_stats = collection_stats.ok_or(ErrorCode::CollectionStatsNotCorrect)?;
+    let (collection_stats_address, _) = Pubkey::find_program_address(
+        &[COLLECTION_STATS, nft_marketplace_account.as_ref(), collection.as_ref(), token_mint_account.as_ref()],
+        program_id,
+    );
+    require!(*collection_stats.key == collection_stats_address, ErrorCode::CollectionStatsNotCorrect);
+    if collection_stats.owner != program_id {
+        return Ok(None);
+    }
+    Ok(Some(Account::try_from(collection_stats)?))
+}
+
+/// Count a new listing in the stats of the verified collection of the NFT, if the collection has stats.
+/// Returns the collection the listing is counted in.
+fn count_collection_listing<'info>(
+    collection_stats: Option<&AccountInfo<'info>>,
+    nft_marketplace_account: &Pubkey,
+    mint_nft_account: &AccountInfo,
+    metadata_account: Option<&AccountInfo>,
+    token_mint_account: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Option<Pubkey>> {
+    let collection = match load_verified_collection(mint_nft_account, metadata_account)? {
+        Some(collection) => collection,
+        None => return Ok(None),
+    };
+    match load_collection_stats(collection_stats, nft_marketplace_account, &collection, token_mint_account, program_id)? {
+        Some(mut stats) => {
+            stats.active_listings += 1;
+            stats.exit(program_id)?;
+            Ok(Some(collection))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Record a sale at `price` in the stats of the verified collection of the NFT and their price observations,
+/// if the collection has stats. `listed_collection` is the collection the listing was counted in, if any.
+fn record_collection_sale<'info>(
+    collection_stats: Option<&AccountInfo<'info>>,
+    price_observations: Option<&AccountInfo<'info>>,
+    nft_marketplace_account: &Pubkey,
+    mint_nft_account: &AccountInfo,
+    metadata_account: Option<&AccountInfo>,
+    token_mint_account: &Pubkey,
+    listed_collection: Option<Pubkey>,
+    price: u64,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let collection = match listed_collection {
+        Some(collection) => collection,
+        None => match load_verified_collection(mint_nft_account, metadata_account)? {
+            Some(collection) => collection,
+            None => return Ok(()),
+        },
+    };
+    let mut stats = match load_collection_stats(collection_stats, nft_marketplace_account, &collection, token_mint_account, program_id)? {
+        Some(stats) => stats,
+        None => {
+            require!(listed_collection.is_none(), ErrorCode::CollectionStatsNotCorrect);
+            return Ok(());
+        }
+    };
+    if listed_collection.is_some() {
+        stats.active_listings = stats.active_listings.saturating_sub(1);
+    }
+    let timestamp = Clock::get()?.unix_timestamp;
+    stats.record_sale(price, timestamp);
+    stats.exit(program_id)?;
+
+    let price_observations = price_observations.ok_or(ErrorCode::PriceObservationsNotCorrect)?;
+    let mut price_observations = Account::<PriceObservations>::try_from(price_observations)?;
+    require!(price_observations.collection_stats == stats.key(), ErrorCode::PriceObservationsNotCorrect);
+    price_observations.record_sale(price, timestamp);
+    price_observations.exit(program_id)
+}
+
+/// Pay the royalty of a sale at `price` from the buyer to each recipient, looking up the currency
+/// token account of the recipient in `royalty_accounts`. Returns the total royalty paid.
+fn transfer_royalty<'info>(
+    royalty: &Royalty,
+    price: u64,
+    royalty_accounts: &[AccountInfo<'info>],
+    currency_token_program: &AccountInfo<'info>,
+    buyer: &AccountInfo<'info>,
+    buyer_token_account: &AccountInfo<'info>,
+    token_mint_account: &AccountInfo<'info>,
+    decimals: u8,
+) -> Result<u64> {
+    let mut total_royalty: u64 = 0;
+    for ((recipient, _), royalty_amount) in royalty.recipients.iter().zip(royalty.get_amounts(price)) {
+        if royalty_amount == 0 {
+            continue;
+        }
+        let royalty_account = royalty_accounts
+            .iter()
+            .find(|account| {
+                TokenAccount::try_from_account_info(account).map_or(false, |token_account| {
+                    token_account.owner == *recipient && token_account.mint == *token_mint_account.key
+                })
+            })
+            .ok_or(ErrorCode::RoyaltyAccountNotCorrect)?;
+
+        transfer_token_checked(
+            currency_token_program,
+            buyer,
+            buyer_token_account,
+            token_mint_account,
+            royalty_account,
+            royalty_amount,
+            decimals,
+            &[],
+            &[]
+        )?;
+
+        total_royalty += royalty_amount;
+    }
+    Ok(total_royalty)
+}
+
+/// Credit `amount` to the claimable balance of `wallet` in `claimable_balance`, creating the account
+/// with the rent paid by `payer` on its first credit
+fn credit_claimable_balance<'info>(
+    claimable_balance: &AccountInfo<'info>,
+    wallet: &Pubkey,
+    nft_marketplace_account: &Pubkey,
+    token_mint_account: &Pubkey,
+    amount: u64,
+    payer: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let (claimable_balance_address, bump) = Pubkey::find_program_address(
+        &[CLAIMABLE_BALANCE, nft_marketplace_account.as_ref(), wallet.as_ref(), token_mint_account.as_ref()],
+        program_id,
+    );
+    require!(*claimable_balance.key == claimable_balance_address, ErrorCode::ClaimableBalanceNotCorrect);
+
+    let mut balance = if claimable_balance.data_is_empty() {
+        let bump_seed = [bump];
+        let signer_seeds: &[&[u8]] = &[
+            CLAIMABLE_BALANCE,
+            nft_marketplace_account.as_ref(),
+            wallet.as_ref(),
+            token_mint_account.as_ref(),
+            &bump_seed,
+        ];
+        create_account(
+            payer,
+            claimable_balance,
+            Rent::get()?.minimum_balance(ClaimableBalance::LEN),
+            ClaimableBalance::LEN as u64,
+            program_id,
+            &[signer_seeds],
+        )?;
+
+        ClaimableBalance {
+            wallet: *wallet,
+            bump,
+            nft_marketplace_account: *nft_marketplace_account,
+            token_mint_account: *token_mint_account,
+            amount: 0,
+        }
+    }
+    else {
+        require!(claimable_balance.owner == program_id, ErrorCode::ClaimableBalanceNotCorrect);
+        ClaimableBalance::try_deserialize(&mut &claimable_balance.try_borrow_data()?[..])?
+    };
+
+    balance.amount = balance.amount.checked_add(amount).ok_or(ErrorCode::ClaimableBalanceNotCorrect)?;
+    balance.try_serialize(&mut &mut claimable_balance.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+/// Credit the royalty of a sale at `price` to the claimable balance of each recipient, looking up the
+/// claimable balance account of the recipient in `royalty_accounts`. Each credit is net of the transfer
+/// fee the currency withholds on its way into the vault. Returns the total royalty credited, fees included.
+fn credit_royalty<'info>(
+    royalty: &Royalty,
+    price: u64,
+    royalty_accounts: &[AccountInfo<'info>],
+    nft_marketplace_account: &Pubkey,
+    token_mint_account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    program_id: &Pubkey,
+    epoch: u64,
+) -> Result<u64> {
+    let mut total_royalty: u64 = 0;
+    for ((recipient, _), royalty_amount) in royalty.recipients.iter().zip(royalty.get_amounts(price)) {
+        if royalty_amount == 0 {
+            continue;
+        }
+        let (claimable_balance_address, _) = Pubkey::find_program_address(
+            &[CLAIMABLE_BALANCE, nft_marketplace_account.as_ref(), recipient.as_ref(), token_mint_account.key.as_ref()],
+            program_id,
+        );
+        let claimable_balance = royalty_accounts
+            .iter()
+            .find(|account| *account.key == claimable_balance_address)
+            .ok_or(ErrorCode::RoyaltyAccountNotCorrect)?;
+
+        let transfer_fee = calculate_transfer_fee(token_mint_account, royalty_amount, epoch)?;
+        credit_claimable_balance(
+            claimable_balance,
+            recipient,
+            nft_marketplace_account,
+            token_mint_account.key,
+            royalty_amount - transfer_fee,
+            payer,
+            program_id,
+        )?;
+
+        total_royalty += royalty_amount;
+    }
+    Ok(total_royalty)
+}
+
+/// Checks `owner` approves the instruction, either signing or being a `Multisig` with enough of its
+/// signers signing among `signers`. Returns the multisig in the latter case.
+fn check_owner_approval(owner: &AccountInfo, signers: &[AccountInfo]) -> Result<Option<Multisig>> {
+    if owner.is_signer {
+        return Ok(None);
+    }
+    let multisig = Account::<Multisig>::try_from(owner).map_err(|_| error!(ErrorCode::OwnerApprovalNotMet))?;
+    let approvals = multisig.count_approvals(signers.iter().filter(|signer| signer.is_signer).map(|signer| signer.key));
+    require!(approvals >= multisig.threshold as usize, ErrorCode::OwnerApprovalNotMet);
+    Ok(Some(multisig.into_inner()))
+}
+
+/// Grow `account` to `len` bytes, `payer` topping up its rent
+fn resize_account<'info>(account: &AccountInfo<'info>, payer: &AccountInfo<'info>, len: usize) -> Result<()> {
+    let lamports = Rent::get()?.minimum_balance(len).saturating_sub(account.lamports());
+    if lamports > 0 {
+        transfer_lamport(payer, account, lamports, &[])?;
+    }
+    account.realloc(len, true)?;
+    Ok(())
+}
+
+/// Fill one item of a sweep at `price` and close its listing to the seller. `accounts` holds the item group
+/// described in `sweep`. Returns the fee quote of the sale, or `None` when the listing cannot be filled anymore.
+fn sweep_listing<'info>(
+    sweep: &SweepContext<'info>,
+    currency: &AcceptedCurrency,
+    price: u64,
+    accounts: &[AccountInfo<'info>],
+    royalty_accounts: &[AccountInfo<'info>],
+    decimals: u8,
+    seed: &[&[u8]],
+    skip_unavailable: bool,
+    program_id: &Pubkey,
+) -> Result<Option<FeeQuote>> {
+    let buyer = &sweep.buyer;
+    let buyer_token_account = &sweep.buyer_token_account;
+    let nft_marketplace_account = &sweep.nft_marketplace_account;
+    let token_mint_account = &sweep.token_mint_account;
+    let program_as_signer = &sweep.program_as_signer;
+    let token_program = &sweep.token_program;
+    let currency_token_program = &sweep.currency_token_program;
+
+    let seller = &accounts[0];
+    let seller_trade_state = &accounts[1];
+    let mint_nft_account = &accounts[2];
+    let nft_token_account = &accounts[3];
+    let buyer_nft_account = &accounts[4];
+    let seller_token_account = &accounts[5];
+    let metadata_account = &accounts[6];
+    let collection_stats = &accounts[7];
+    let price_observations = &accounts[8];
+
+    let trade_state = match Account::<SellerTradeState>::try_from(seller_trade_state) {
+        Ok(trade_state) => trade_state,
+        Err(_) => return Ok(None),
+    };
+    require!(trade_state.version == SellerTradeState::VERSION, ErrorCode::AccountVersionNotSupported);
+    require!(trade_state.seller == *seller.key, ErrorCode::SellerNotCorrect);
+    require!(trade_state.mint_nft_account == *mint_nft_account.key, ErrorCode::MintNFTAccountNotCorrect);
+    require!(trade_state.nft_marketplace_account == nft_marketplace_account.key(), ErrorCode::NFTMarketplaceAccountNotCorrect);
+    require!(trade_state.nft_token_account == *nft_token_account.key, ErrorCode::NFTTokenAccountNotCorrect);
+    require!(trade_state.token_mint_account == *token_mint_account.key, ErrorCode::TokenMintAccountNotCorrect);
+    require!(*mint_nft_account.owner == *token_program.key, ErrorCode::TokenProgramNotCorrect);
+
+    if trade_state.is_programmable || trade_state.usd_price.is_some() || trade_state.claimable_payout {
+        return Ok(None);
+    }
+    if trade_state.reserved_buyer.map_or(false, |reserved_buyer| reserved_buyer != *buyer.key)
+        || trade_state.token_gate.is_some()
+    {
+        return Ok(None);
+    }
+    if trade_state.list_price != price {
+        if skip_unavailable {
+            return Ok(None);
+        }
+        return err!(ErrorCode::PriceNotCorrect);
+    }
+
+    // The seller proceeds must reach a currency token account of the seller
+    let is_seller_token_account = TokenAccount::try_from_account_info(seller_token_account)
+        .map_or(false, |token_account| {
+            token_account.owner == trade_state.seller && token_account.mint == *token_mint_account.key
+        });
+    if !is_seller_token_account {
+        if skip_unavailable {
+            return Ok(None);
+        }
+        return err!(ErrorCode::SellerTokenAccountNotCorrect);
+    }
+
+    // The seller moved the NFT or revoked the marketplace since listing
+    let token_account = TokenAccount::try_from_account_info(nft_token_account)?;
+    if token_account.amount != 1
+        || token_account.delegate != COption::Some(program_as_signer.key())
+        || token_account.delegated_amount < 1
+    {
+        return Ok(None);
+    }
+
+    // Sellers get the fees of the tier they held the asset of when listing
+    let mut currency = currency.clone();
+    if let Some(tier) = nft_marketplace_account.get_fee_tier_at(trade_state.seller_fee_tier) {
+        currency.seller_fee_basis_points = currency.seller_fee_basis_points.min(tier.seller_fee_basis_points);
+    }
+    let mut quote = currency.get_fee_quote(price).ok_or(ErrorCode::PriceNotCorrect)?;
+
+    if *mint_nft_account.owner == TOKEN_2022_PROGRAM_ID {
+        require!(
+            get_transfer_hook_program_id(&mint_nft_account.try_borrow_data()?)?.is_none(),
+            ErrorCode::MintExtensionNotSupported
+        );
+        transfer_token_checked(
+            token_program,
+            program_as_signer,
+            nft_token_account,
+            mint_nft_account,
+            buyer_nft_account,
+            1,
+            0,
+            &[],
+            &[seed],
+        )?;
+    }
+    else {
+        transfer_token(
+            program_as_signer,
+            nft_token_account,
+            buyer_nft_account,
+            1,
+            &[seed],
+        )?;
+    }
+
+    // Transfer royalty to creators
+    let royalty_share = nft_marketplace_account.royalty_policy.get_royalty_share(None);
+    let mut royalty = load_royalty(mint_nft_account, Some(metadata_account))?;
+    royalty.apply_share(royalty_share);
+    let total_royalty = transfer_royalty(
+        &royalty,
+        price,
+        royalty_accounts,
+        currency_token_program,
+        buyer,
+        buyer_token_account,
+        token_mint_account,
+        decimals,
+    )?;
+
+    // Transfer token to seller, who pays the royalty the policy enforces and the buyer the rest
+    let seller_royalty = nft_marketplace_account.royalty_policy.get_seller_royalty(total_royalty, royalty_share);
+    let seller_amount = quote.seller_receives.checked_sub(seller_royalty).ok_or(ErrorCode::PriceNotCorrect)?;
+    transfer_token_checked(
+        currency_token_program,
+        buyer,
+        buyer_token_account,
+        token_mint_account,
+        seller_token_account,
+        seller_amount,
+        decimals,
+        &[],
+        &[]
+    )?;
+
+    record_collection_sale(
+        Some(collection_stats),
+        Some(price_observations),
+        &nft_marketplace_account.key(),
+        mint_nft_account,
+        Some(metadata_account),
+        token_mint_account.key,
+        trade_state.collection,
+        price,
+        program_id,
+    )?;
+
+    trade_state.close(seller.clone())?;
+
+    // Royalty left to the buyer counts in the spend cap of the sweep
+    quote.buyer_pays = quote.buyer_pays
+        .checked_add(total_royalty - seller_royalty)
+        .ok_or(ErrorCode::PriceNotCorrect)?;
+
+    Ok(Some(quote))
+}
+
+/// List one item of a batch at `list_price`. `accounts` holds the item group described in `batch_list`.
+fn list_batch_item<'info>(
+    batch: &BatchListingNftContext<'info>,
+    currency: &AcceptedCurrency,
+    list_price: u64,
+    seller_fee_tier: Option<u8>,
+    accounts: &[AccountInfo<'info>],
+    rent: &Rent,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let seller = &batch.seller;
+    let nft_marketplace_account = &batch.nft_marketplace_account;
+    let token_mint_account = &batch.token_mint_account;
+    let program_as_signer = &batch.program_as_signer;
+    let token_program = &batch.token_program;
+
+    let mint_nft_account = &accounts[0];
+    let nft_token_account = &accounts[1];
+    let seller_trade_state = &accounts[2];
+    let metadata_account = &accounts[3];
+    let collection_stats = &accounts[4];
+
+    require!(currency.is_list_price_allowed(list_price), ErrorCode::ListPriceOutOfBounds);
+    require!(*mint_nft_account.owner == *token_program.key, ErrorCode::TokenProgramNotCorrect);
+    if *mint_nft_account.owner == TOKEN_2022_PROGRAM_ID {
+        check_token_2022_nft(mint_nft_account)?;
+    }
+
+    let token_account = TokenAccount::try_from_account_info(nft_token_account)?;
+    require!(token_account.owner == *seller.key, ErrorCode::NFTTokenAccountNotCorrect);
+    require!(token_account.mint == *mint_nft_account.key, ErrorCode::MintNFTAccountNotCorrect);
+    require!(token_account.amount == 1, ErrorCode::NFTTokenAccountNotCorrect);
+
+    // Same seeds as `ListingNftContext`, which only keeps the first byte of the price
+    let price_seed = [list_price as u8];
+    let seeds: &[&[u8]] = &[
+        LISTING,
+        seller.key.as_ref(),
+        &price_seed,
+        mint_nft_account.key.as_ref(),
+        nft_marketplace_account.to_account_info().key.as_ref(),
+        nft_token_account.key.as_ref(),
+        token_mint_account.key.as_ref(),
+    ];
+    let (trade_state_address, bump) = Pubkey::find_program_address(seeds, program_id);
+    require!(*seller_trade_state.key == trade_state_address, ErrorCode::SellerTradeStateNotCorrect);
+    require!(seller_trade_state.data_is_empty(), ErrorCode::SellerTradeStateNotCorrect);
+
+    let bump_seed = [bump];
+    let signer_seeds: &[&[u8]] = &[
+        LISTING,
+        seller.key.as_ref(),
+        &price_seed,
+        mint_nft_account.key.as_ref(),
+        nft_marketplace_account.to_account_info().key.as_ref(),
+        nft_token_account.key.as_ref(),
+        token_mint_account.key.as_ref(),
+        &bump_seed,
+    ];
+    create_account(
+        &seller.to_account_info(),
+        seller_trade_state,
+        rent.minimum_balance(SellerTradeState::LEN),
+        SellerTradeState::LEN as u64,
+        program_id,
+        &[signer_seeds],
+    )?;
+
+    let collection = count_collection_listing(
+        Some(collection_stats),
+        nft_marketplace_account.to_account_info().key,
+        mint_nft_account,
+        Some(metadata_account),
+        token_mint_account.key,
+        program_id,
+    )?;
+
+    let trade_state = SellerTradeState {
+        version: SellerTradeState::VERSION,
+        seller: *seller.key,
+        bump,
+        list_price,
+        mint_nft_account: *mint_nft_account.key,
+        nft_marketplace_account: *nft_marketplace_account.to_account_info().key,
+        nft_token_account: *nft_token_account.key,
+        token_mint_account: *token_mint_account.key,
+        is_programmable: false,
+        usd_price: None,
+        price_feed: None,
+        reserved_buyer: None,
+        token_gate: None,
+        collection,
+        seller_fee_tier,
+        claimable_payout: false,
+        reserved: [0; 29],
+    };
+    trade_state.try_serialize(&mut &mut seller_trade_state.try_borrow_mut_data()?[..])?;
+
+    emit!(ListNftEvent {
+        seller_trade_state: *seller_trade_state.key,
+        seller: trade_state.seller,
+        mint_nft_account: trade_state.mint_nft_account,
+        nft_marketplace_account: trade_state.nft_marketplace_account,
+        token_mint_account: trade_state.token_mint_account,
+        list_price: trade_state.list_price,
+        usd_price: None,
+        reserved_buyer: None,
+    });
+
+    approve_token(
+        token_program,
+        &seller.to_account_info(),
+        nft_token_account,
+        &program_as_signer.to_account_info(),
+        1,
+    )?;
+
+    Ok(())
 }
 
 #[program]
@@ -75,18 +843,12 @@ mod nft_marketplace {
         let rent = &ctx.accounts.rent;
         let token_metadata_program = &ctx.accounts.token_metadata_program;
 
-        let token_collection_details : Option<mpl_token_metadata::state::CollectionDetails>;
-
-        if let Some(size) = size {
-            token_collection_details = Some(
-                mpl_token_metadata::state::CollectionDetails::V1 {
-                    size,
-                }
-            )
-        }
-        else {
-            token_collection_details = None;
-        }
+        #[allow(deprecated)]
+        let token_collection_details = size.map(|size| {
+            mpl_token_metadata::state::CollectionDetails::V1 {
+                size,
+            }
+        });
 
         let instruction = mpl_instruction::create_metadata_accounts_v3(
             TOKEN_METADATA_ID,
@@ -119,8 +881,7 @@ mod nft_marketplace {
                 system_program.to_account_info(),
                 rent.to_account_info(),
                 token_metadata_program.to_account_info(),
-            ])
-        .expect("CPI failed");
+            ])?;
         Ok(())
     }
 
@@ -160,8 +921,7 @@ mod nft_marketplace {
             token_metadata_program.to_account_info(),
             master_edition_account.to_account_info(),
             token_program.to_account_info(),
-        ])
-        .expect("CPI failed");
+        ])?;
 
         Ok(())
     }
@@ -180,7 +940,7 @@ mod nft_marketplace {
 
         if let Some(data_record) = data {
             msg!("Update data: {:?}", data_record.clone());
-            update_data = Some(data_record.into());
+            update_data = Some(data_record);
         } else {
             update_data = None;
         }
@@ -199,7 +959,7 @@ mod nft_marketplace {
             metadata_account.to_account_info(),
             update_authority.to_account_info(),
             token_metadata_program.to_account_info(),
-        ]).expect("CPI failed");
+        ])?;
 
         Ok(())
     }
@@ -207,161 +967,1835 @@ mod nft_marketplace {
     pub fn init_marketplace(
         ctx: Context<InitMarketplaceContext>,
         seller_fee_basis_points: u16,
-        bump: u8,
-        fee_bump: u8,
+        buyer_fee_basis_points: u16,
         ) -> Result<()> {
+            check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
             let marketplace = &mut ctx.accounts.marketplace;
             let spl_token_mint = &ctx.accounts.spl_token_mint;
             let fee_account = & ctx.accounts.fee_account;
             let owner = &ctx.accounts.owner;
 
+            require!(is_supported_currency_mint(&spl_token_mint.try_borrow_data()?)?, ErrorCode::MintExtensionNotSupported);
+            require!(
+                seller_fee_basis_points <= 10000 && buyer_fee_basis_points <= 10000,
+                ErrorCode::FeeBasisPointsNotCorrect
+            );
+
+            marketplace.version = MarketplaceNFT::VERSION;
             marketplace.seller_fee_basis_points = seller_fee_basis_points;
+            marketplace.buyer_fee_basis_points = buyer_fee_basis_points;
             marketplace.spl_token_mint = *spl_token_mint.to_account_info().key;
             marketplace.fee_account = *fee_account.to_account_info().key;
-            marketplace.bump = bump;
-            marketplace.fee_bump = fee_bump;
+            marketplace.bump = *ctx.bumps.get("marketplace").unwrap();
+            marketplace.signer_bump = *ctx.bumps.get("program_as_signer").unwrap();
             marketplace.owner = *owner.to_account_info().key;
+            marketplace.accepted_currencies = vec![AcceptedCurrency {
+                mint: marketplace.spl_token_mint,
+                fee_account: marketplace.fee_account,
+                seller_fee_basis_points,
+                buyer_fee_basis_points,
+                min_list_price: 0,
+                max_list_price: 0,
+                price_feed: None,
+            }];
 
             Ok(())
     }
 
-    pub fn list_nft_to_marketplace(
-        ctx: Context<ListingNftContext>,
-        list_price: u64,
-        bump: u8
+    pub fn set_accepted_currency(
+        ctx: Context<SetAcceptedCurrencyContext>,
+        seller_fee_basis_points: u16,
+        buyer_fee_basis_points: u16,
     ) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
 
-        let seller_trade_state = &mut ctx.accounts.seller_trade_state;
-        let seller = &ctx.accounts.seller;
-        let mint_nft_account = &ctx.accounts.mint_nft_account;
-        let nft_marketplace_account = &ctx.accounts.nft_marketplace_account;
-        let nft_token_account = &ctx.accounts.nft_token_account;
-        let token_mint_account = &ctx.accounts.token_mint_account;
-        let program_as_signer = &ctx.accounts.program_as_signer;
-        let token_program = &ctx.accounts.token_program;
+        let marketplace = &mut ctx.accounts.marketplace;
+        let spl_token_mint = &ctx.accounts.spl_token_mint;
+        let fee_account = &ctx.accounts.fee_account;
 
-        seller_trade_state.list_price = list_price;
-        seller_trade_state.seller = *seller.to_account_info().key;
-        seller_trade_state.mint_nft_account = *mint_nft_account.to_account_info().key;
-        seller_trade_state.nft_marketplace_account = *nft_marketplace_account.to_account_info().key;
-        seller_trade_state.nft_token_account = *nft_token_account.to_account_info().key;
-        seller_trade_state.token_mint_account = *token_mint_account.to_account_info().key;
-        seller_trade_state.bump = bump;
+        require!(is_supported_currency_mint(&spl_token_mint.try_borrow_data()?)?, ErrorCode::MintExtensionNotSupported);
+        require!(
+            seller_fee_basis_points <= 10000 && buyer_fee_basis_points <= 10000,
+            ErrorCode::FeeBasisPointsNotCorrect
+        );
+
+        // Fees of accepted currencies only change through `queue_pending_change` once there is a delay
+        require!(
+            marketplace.change_delay == 0 || marketplace.get_accepted_currency(spl_token_mint.key).is_none(),
+            ErrorCode::ChangeTimelocked
+        );
+
+        // Updating a currency keeps its listing price bounds and price feed
+        let (min_list_price, max_list_price, price_feed) = marketplace
+            .get_accepted_currency(spl_token_mint.key)
+            .map_or((0, 0, None), |accepted| (accepted.min_list_price, accepted.max_list_price, accepted.price_feed));
 
-        let data = ApproveTokenParams {
-            instruction: 4,
-            amount: 1,
+        let currency = AcceptedCurrency {
+            mint: *spl_token_mint.key,
+            fee_account: *fee_account.key,
+            seller_fee_basis_points,
+            buyer_fee_basis_points,
+            min_list_price,
+            max_list_price,
+            price_feed,
         };
 
-        let data = data.try_to_vec().unwrap();
+        if currency.mint == marketplace.spl_token_mint {
+            marketplace.fee_account = currency.fee_account;
+            marketplace.seller_fee_basis_points = currency.seller_fee_basis_points;
+            marketplace.buyer_fee_basis_points = currency.buyer_fee_basis_points;
+        }
 
-        let accounts = vec![
-            AccountMeta::new(*nft_token_account.key, false),
-            AccountMeta::new(*program_as_signer.key, false),
-            AccountMeta::new_readonly(*seller.key, true),
-        ];
+        match marketplace.accepted_currencies.iter_mut().find(|accepted| accepted.mint == currency.mint) {
+            Some(accepted) => *accepted = currency,
+            None => {
+                require!(marketplace.accepted_currencies.len() < MAX_ACCEPTED_CURRENCIES, ErrorCode::TooManyAcceptedCurrencies);
+                marketplace.accepted_currencies.push(currency);
+            }
+        }
 
-        let instruction = Instruction {
-            program_id: *token_program.key,
-            accounts,
-            data,
+        Ok(())
+    }
+
+    /// Bound the prices listings can ask in a currency, 0 for no bound
+    pub fn set_list_price_bounds(
+        ctx: Context<SetListPriceBoundsContext>,
+        min_list_price: u64,
+        max_list_price: u64,
+    ) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
+        let marketplace = &mut ctx.accounts.marketplace;
+        let spl_token_mint = &ctx.accounts.spl_token_mint;
+
+        require!(max_list_price == 0 || min_list_price <= max_list_price, ErrorCode::ListPriceOutOfBounds);
+
+        let currency = marketplace
+            .accepted_currencies
+            .iter_mut()
+            .find(|accepted| accepted.mint == *spl_token_mint.key)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+        currency.min_list_price = min_list_price;
+        currency.max_list_price = max_list_price;
+
+        Ok(())
+    }
+
+    /// Trust `price_feed` to settle listings pegged to USD in a currency, or stop USD-pegged listings with `None`.
+    /// The feed must be owned by `PRICE_FEED_PROGRAM`.
+    pub fn set_price_feed(ctx: Context<SetPriceFeedContext>, price_feed: Option<Pubkey>) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
+        let marketplace = &mut ctx.accounts.marketplace;
+        let spl_token_mint = &ctx.accounts.spl_token_mint;
+
+        let currency = marketplace
+            .accepted_currencies
+            .iter_mut()
+            .find(|accepted| accepted.mint == *spl_token_mint.key)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+        currency.price_feed = price_feed;
+
+        Ok(())
+    }
+
+    /// Rewrite an unversioned marketplace in the current layout, growing it with the extra rent paid by `payer`.
+    /// Bumps of unversioned marketplaces were passed by clients, they are replaced by the canonical ones.
+    pub fn migrate_marketplace(ctx: Context<MigrateMarketplaceContext>) -> Result<()> {
+        let marketplace = &ctx.accounts.marketplace;
+        let payer = &ctx.accounts.payer;
+
+        let mut marketplace_data = {
+            let data = marketplace.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == MarketplaceNFT::DISCRIMINATOR,
+                ErrorCode::NFTMarketplaceAccountNotCorrect
+            );
+            if data.len() == MarketplaceNFT::LEN {
+                require!(data[8] == MarketplaceNFT::VERSION, ErrorCode::AccountVersionNotSupported);
+                return Ok(());
+            }
+            require!(data.len() == MarketplaceNFT::BASELINE_LEN, ErrorCode::AccountVersionNotSupported);
+            MarketplaceNFT::deserialize_baseline(&mut &data[8..])?
         };
 
-        msg!("DEBUG: Delegate account instruction {:?}", instruction);
+        marketplace_data.version = MarketplaceNFT::VERSION;
+        marketplace_data.bump = Pubkey::find_program_address(
+            &[MARKETPLACE, marketplace_data.owner.as_ref(), marketplace_data.spl_token_mint.as_ref()],
+            ctx.program_id,
+        ).1;
+        marketplace_data.signer_bump = Pubkey::find_program_address(
+            &[MARKETPLACE, marketplace.key.as_ref(), SIGNER],
+            ctx.program_id,
+        ).1;
+
+        resize_account(marketplace, payer, MarketplaceNFT::LEN)?;
+        marketplace_data.try_serialize(&mut &mut marketplace.try_borrow_mut_data()?[..])?;
 
-        invoke(&instruction, &[
-            nft_token_account.clone(),
-            program_as_signer.to_account_info().clone(),
-            seller.to_account_info().clone(),
-            token_program.clone(),
-        ]).expect("CPI failed");
 
         Ok(())
     }
 
-    pub fn execute_sale(ctx: Context<BuyNftContext>, amount: u64, _bump: u8) -> Result<()> {
-        let buyer = &ctx.accounts.buyer;
-        let seller = &ctx.accounts.seller;
-        let buyer_nft_account = &ctx.accounts.buyer_nft_account;
-        let buyer_token_account = &ctx.accounts.buyer_token_account;
-        let seller_token_account = &ctx.accounts.seller_token_account;
+    /// Rewrite an unversioned listing in the current layout, growing it with the extra rent paid by `payer`.
+    /// Bumps of unversioned listings were passed by clients, they are replaced by the canonical ones.
+    ///
+    /// Unversioned listings delegated their NFT to the program signer shared by every marketplace,
+    /// `[MARKETPLACE, SIGNER]`, so they cannot be sold anymore. Their sellers cancel them, which revokes
+    /// the legacy delegate, and list again.
+    pub fn migrate_listing(ctx: Context<MigrateListingContext>) -> Result<()> {
         let seller_trade_state = &ctx.accounts.seller_trade_state;
-        let mint_nft_account = &ctx.accounts.mint_nft_account;
-        let nft_marketplace_account = &ctx.accounts.nft_marketplace_account;
-        let nft_token_account = &ctx.accounts.nft_token_account;
-        let token_mint_account = &ctx.accounts.token_mint_account;
-        let program_as_signer = &ctx.accounts.program_as_signer;
-        // let authority = &ctx.accounts.authority;
-        let fee_account = &mut ctx.accounts.fee_account;
-        // let token_program = &ctx.accounts.token_program;
+        let payer = &ctx.accounts.payer;
 
-        require!(seller_trade_state.list_price == amount, ErrorCode::PriceNotCorrect);
-        require!(seller_trade_state.seller == *seller.to_account_info().key, ErrorCode::SellerNotCorrect);
-        require!(seller_trade_state.mint_nft_account == *mint_nft_account.to_account_info().key, ErrorCode::MintNFTAccountNotCorrect);
-        require!(seller_trade_state.nft_marketplace_account == *nft_marketplace_account.to_account_info().key, ErrorCode::NFTMarketplaceAccountNotCorrect);
-        require!(seller_trade_state.nft_token_account == *nft_token_account.to_account_info().key, ErrorCode::NFTTokenAccountNotCorrect);
-        require!(seller_trade_state.token_mint_account == *token_mint_account.to_account_info().key, ErrorCode::TokenMintAccountNotCorrect);
+        let mut trade_state = {
+            let data = seller_trade_state.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == SellerTradeState::DISCRIMINATOR,
+                ErrorCode::SellerTradeStateNotCorrect
+            );
+            if data.len() == SellerTradeState::LEN {
+                require!(data[8] == SellerTradeState::VERSION, ErrorCode::AccountVersionNotSupported);
+                return Ok(());
+            }
+            require!(data.len() == SellerTradeState::BASELINE_LEN, ErrorCode::AccountVersionNotSupported);
+            SellerTradeState::deserialize_baseline(&mut &data[8..])?
+        };
 
-        let fee = amount * nft_marketplace_account.seller_fee_basis_points as u64 / 10000;
+        // Same seeds as `ListingNftContext`, which only keeps the first byte of the price
+        let price_seed = [trade_state.list_price as u8];
+        let (trade_state_address, bump) = Pubkey::find_program_address(
+            &[
+                LISTING,
+                trade_state.seller.as_ref(),
+                &price_seed,
+                trade_state.mint_nft_account.as_ref(),
+                trade_state.nft_marketplace_account.as_ref(),
+                trade_state.nft_token_account.as_ref(),
+                trade_state.token_mint_account.as_ref(),
+            ],
+            ctx.program_id,
+        );
+        require!(trade_state_address == *seller_trade_state.key, ErrorCode::SellerTradeStateNotCorrect);
+        trade_state.bump = bump;
 
-        let seed : &[&[u8]] = &[
-            b"MARKETPLACE".as_ref(),
-            b"MARKETPLACE_SIGNER".as_ref(),
-            &[_bump]
-        ];
+        resize_account(seller_trade_state, payer, SellerTradeState::LEN)?;
+        trade_state.try_serialize(&mut &mut seller_trade_state.try_borrow_mut_data()?[..])?;
 
-        // Transfer NFT to buyer
-        transfer_token(
-            &program_as_signer,
-            &nft_token_account,
-            &buyer_nft_account,
-            1,
-            &[seed],
-        ).expect("CPI failed");
 
-        // Transfer token to seller
-        transfer_token(
-            &buyer,
-            &buyer_token_account,
-            &seller_token_account,
-            amount - fee,
-            &[]
-        ).expect("CPI failed");
+        Ok(())
+    }
 
-        msg!("DEBUG: fee {:?}", fee);
+    /// Stop accepting a currency other than the primary one. Its fee account must be emptied with `withdraw_fee` first.
+    pub fn remove_accepted_currency(ctx: Context<RemoveAcceptedCurrencyContext>) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
 
-        // Transfer fee to fee account
-        transfer_token(
-            &buyer,
-            &buyer_token_account,
-            &fee_account.to_account_info(),
-            fee,
-            &[]
-        ).expect("CPI failed");
+        let marketplace = &mut ctx.accounts.marketplace;
+        let spl_token_mint = &ctx.accounts.spl_token_mint;
 
-        // fee_account.amount += fee;
+        // The primary currency is part of the marketplace address
+        require!(marketplace.spl_token_mint != *spl_token_mint.key, ErrorCode::PrimaryCurrencyNotRemovable);
+        require!(marketplace.get_accepted_currency(spl_token_mint.key).is_some(), ErrorCode::CurrencyNotAccepted);
+
+        // Removing then adding back a currency would change its fees without the delay,
+        // the delay has to be queued down to 0 first
+        require!(marketplace.change_delay == 0, ErrorCode::ChangeTimelocked);
+
+        // Fees can only be withdrawn in accepted currencies, they have to be withdrawn before removing it
+        let fee_account = &ctx.accounts.fee_account;
+        let is_fee_account_empty = fee_account.data_is_empty()
+            || TokenAccount::try_from_account_info(fee_account)?.amount == 0;
+        require!(is_fee_account_empty, ErrorCode::FeeAccountNotEmpty);
+
+        marketplace.accepted_currencies.retain(|accepted| accepted.mint != *spl_token_mint.key);
 
         Ok(())
+    }
+
+    /// Require buyers of every listing without its own gate to meet `token_gate`, or lift the requirement with `None`
+    pub fn set_token_gate(ctx: Context<SetTokenGateContext>, token_gate: Option<TokenGate>) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
+        let marketplace = &mut ctx.accounts.marketplace;
+
+        marketplace.token_gate = token_gate;
 
+        Ok(())
     }
 
-    pub fn withdraw_fee(ctx: Context<WithdrawFromFeeAccountContext>, amount: u64) -> Result<()> {
-        let fee_account = &ctx.accounts.fee_account;
-        let owner = &ctx.accounts.owner;
-        let destination_account = &ctx.accounts.destination_account;
+    /// Share of the fee, in basis points of the fee, paid to the referrer of a sale
+    pub fn set_referral_basis_points(ctx: Context<SetReferralBasisPointsContext>, referral_basis_points: u16) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
 
-        // Transfer fee to fee account
-        transfer_token(
-            &owner,
-            &fee_account.to_account_info(),
-            &destination_account.to_account_info(),
-            amount,
-            &[]
-        ).expect("CPI failed");
+        let marketplace = &mut ctx.accounts.marketplace;
 
-        msg!("DEBUG: seed {:?}", amount);
+        require!(referral_basis_points <= 10000, ErrorCode::ReferralBasisPointsNotCorrect);
+
+        marketplace.referral_basis_points = referral_basis_points;
+
+        Ok(())
+    }
+
+    /// Replace the fee tier table. Holders of `fee_tier_asset` pay the fees of the highest tier
+    /// their holdings qualify for, when lower than the fees of the currency. Sellers present their
+    /// holdings when listing, their listings keep the index of the tier they qualified for.
+    pub fn set_fee_tiers(
+        ctx: Context<SetFeeTiersContext>,
+        fee_tier_asset: Option<FeeTierAsset>,
+        fee_tiers: Vec<FeeTier>,
+    ) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
+        let marketplace = &mut ctx.accounts.marketplace;
+
+        require!(fee_tiers.len() <= MAX_FEE_TIERS, ErrorCode::FeeTiersNotCorrect);
+        require!(fee_tier_asset.is_some() || fee_tiers.is_empty(), ErrorCode::FeeTiersNotCorrect);
+        require!(
+            fee_tiers.windows(2).all(|tiers| tiers[0].min_amount < tiers[1].min_amount),
+            ErrorCode::FeeTiersNotCorrect
+        );
+        require!(
+            fee_tiers.iter().all(|tier| tier.seller_fee_basis_points <= 10000 && tier.buyer_fee_basis_points <= 10000),
+            ErrorCode::FeeTiersNotCorrect
+        );
+
+        marketplace.fee_tier_asset = fee_tier_asset;
+        marketplace.fee_tiers = fee_tiers;
+
+        Ok(())
+    }
+
+    /// Set the share of the creator royalty buyers pay on sales
+    pub fn set_royalty_policy(ctx: Context<SetRoyaltyPolicyContext>, royalty_policy: RoyaltyPolicy) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
+        let marketplace = &mut ctx.accounts.marketplace;
+
+        if let RoyaltyPolicy::Minimum { basis_points } = royalty_policy {
+            require!(basis_points <= 10000, ErrorCode::RoyaltyPolicyNotCorrect);
+        }
+
+        marketplace.royalty_policy = royalty_policy;
+
+        Ok(())
+    }
+
+    /// Share fees paid out by `distribute_fees` among recipients, or clear the table with no recipients
+    pub fn set_fee_splits(ctx: Context<SetFeeSplitsContext>, fee_splits: Vec<FeeSplit>) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
+        let marketplace = &mut ctx.accounts.marketplace;
+
+        // Fee splits only change through `queue_pending_change` once there is a delay
+        require!(marketplace.change_delay == 0, ErrorCode::ChangeTimelocked);
+        require!(FeeSplit::is_valid_table(&fee_splits), ErrorCode::FeeSplitsNotCorrect);
+
+        marketplace.fee_splits = fee_splits;
+
+        Ok(())
+    }
+
+    /// Raise the delay fee changes are queued for. Lowering it is itself queued with `queue_pending_change`.
+    pub fn set_change_delay(ctx: Context<SetChangeDelayContext>, change_delay: i64) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
+        let marketplace = &mut ctx.accounts.marketplace;
+
+        require!(
+            change_delay >= marketplace.change_delay && change_delay <= MAX_CHANGE_DELAY,
+            ErrorCode::ChangeDelayNotCorrect
+        );
+
+        marketplace.change_delay = change_delay;
+
+        Ok(())
+    }
+
+    /// Queue new fees and fee account for an accepted currency, with the new change delay and optionally
+    /// new fee splits of the marketplace, applicable by anyone once the current change delay has passed
+    pub fn queue_pending_change(
+        ctx: Context<QueuePendingChangeContext>,
+        seller_fee_basis_points: u16,
+        buyer_fee_basis_points: u16,
+        change_delay: i64,
+        fee_splits: Option<Vec<FeeSplit>>,
+    ) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
+        let pending_change = &mut ctx.accounts.pending_change;
+        let marketplace = &ctx.accounts.marketplace;
+        let spl_token_mint = &ctx.accounts.spl_token_mint;
+
+        require!(marketplace.get_accepted_currency(spl_token_mint.key).is_some(), ErrorCode::CurrencyNotAccepted);
+        require!((0..=MAX_CHANGE_DELAY).contains(&change_delay), ErrorCode::ChangeDelayNotCorrect);
+        require!(
+            seller_fee_basis_points <= 10000 && buyer_fee_basis_points <= 10000,
+            ErrorCode::FeeBasisPointsNotCorrect
+        );
+        require!(
+            fee_splits.as_ref().map_or(true, |fee_splits| FeeSplit::is_valid_table(fee_splits)),
+            ErrorCode::FeeSplitsNotCorrect
+        );
+
+        pending_change.marketplace = marketplace.key();
+        pending_change.spl_token_mint = *spl_token_mint.key;
+        pending_change.payer = ctx.accounts.payer.key();
+        pending_change.bump = *ctx.bumps.get("pending_change").unwrap();
+        pending_change.fee_account = ctx.accounts.fee_account.key();
+        pending_change.seller_fee_basis_points = seller_fee_basis_points;
+        pending_change.buyer_fee_basis_points = buyer_fee_basis_points;
+        pending_change.change_delay = change_delay;
+        pending_change.effective_time = Clock::get()?.unix_timestamp + marketplace.change_delay;
+        pending_change.fee_splits = fee_splits.clone();
+
+        emit!(PendingChangeEvent {
+            pending_change: pending_change.key(),
+            nft_marketplace_account: pending_change.marketplace,
+            token_mint_account: pending_change.spl_token_mint,
+            fee_account: pending_change.fee_account,
+            seller_fee_basis_points,
+            buyer_fee_basis_points,
+            change_delay,
+            effective_time: pending_change.effective_time,
+            fee_splits,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_pending_change(ctx: Context<CancelPendingChangeContext>) -> Result<()> {
+        check_owner_approval(&ctx.accounts.owner, ctx.remaining_accounts)?;
+
+
+        Ok(())
+    }
+
+    pub fn apply_pending_change(ctx: Context<ApplyPendingChangeContext>) -> Result<()> {
+        let pending_change = &ctx.accounts.pending_change;
+        let marketplace = &mut ctx.accounts.marketplace;
+
+        require!(Clock::get()?.unix_timestamp >= pending_change.effective_time, ErrorCode::PendingChangeNotEffective);
+
+        let currency = marketplace
+            .accepted_currencies
+            .iter_mut()
+            .find(|accepted| accepted.mint == pending_change.spl_token_mint)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+        currency.fee_account = pending_change.fee_account;
+        currency.seller_fee_basis_points = pending_change.seller_fee_basis_points;
+        currency.buyer_fee_basis_points = pending_change.buyer_fee_basis_points;
+
+        if pending_change.spl_token_mint == marketplace.spl_token_mint {
+            marketplace.fee_account = pending_change.fee_account;
+            marketplace.seller_fee_basis_points = pending_change.seller_fee_basis_points;
+            marketplace.buyer_fee_basis_points = pending_change.buyer_fee_basis_points;
+        }
+        marketplace.change_delay = pending_change.change_delay;
+        if let Some(fee_splits) = &pending_change.fee_splits {
+            marketplace.fee_splits = fee_splits.clone();
+        }
+
+
+        Ok(())
+    }
+
+    /// Create the trading stats of a verified collection, recording sales in one currency of the marketplace
+    pub fn init_collection_stats(ctx: Context<InitCollectionStatsContext>, collection: Pubkey) -> Result<()> {
+        let collection_stats = &mut ctx.accounts.collection_stats;
+
+        collection_stats.nft_marketplace_account = ctx.accounts.nft_marketplace_account.key();
+        collection_stats.collection = collection;
+        collection_stats.token_mint_account = ctx.accounts.token_mint_account.key();
+        collection_stats.bump = *ctx.bumps.get("collection_stats").unwrap();
+
+        let price_observations = &mut ctx.accounts.price_observations;
+        price_observations.collection_stats = collection_stats.key();
+        price_observations.bump = *ctx.bumps.get("price_observations").unwrap();
+
+
+        Ok(())
+    }
+
+    /// Create the price observations of collection stats created before sales were observed
+    pub fn init_price_observations(ctx: Context<InitPriceObservationsContext>) -> Result<()> {
+        let price_observations = &mut ctx.accounts.price_observations;
+        price_observations.collection_stats = ctx.accounts.collection_stats.key();
+        price_observations.bump = *ctx.bumps.get("price_observations").unwrap();
+
+
+        Ok(())
+    }
+
+    /// Time-weighted average sale price of a collection over the last `window` seconds.
+    /// Other programs read it through CPI with the `cpi` feature.
+    pub fn get_twap(ctx: Context<GetTwapContext>, window: i64) -> Result<u64> {
+        let price_observations = &ctx.accounts.price_observations;
+
+        let now = Clock::get()?.unix_timestamp;
+        Ok(price_observations.get_twap(window, now).ok_or(ErrorCode::TwapWindowNotCovered)?)
+    }
+
+    /// Quote the fees and total cost of a sale at `price` in the given currency, for UIs to simulate
+    pub fn quote_sale(ctx: Context<QuoteSaleContext>, price: u64) -> Result<FeeQuote> {
+        let nft_marketplace_account = &ctx.accounts.nft_marketplace_account;
+        let token_mint_account = &ctx.accounts.token_mint_account;
+
+        let currency = nft_marketplace_account
+            .get_accepted_currency(token_mint_account.key)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+
+        Ok(currency.get_fee_quote(price).ok_or(ErrorCode::PriceNotCorrect)?)
+    }
+
+    pub fn list_nft_to_marketplace<'info>(
+        ctx: Context<'_, '_, '_, 'info, ListingNftContext<'info>>,
+        list_price: u64,
+        usd_price: Option<u64>,
+        reserved_buyer: Option<Pubkey>,
+        token_gate: Option<TokenGate>,
+        claimable_payout: bool,
+    ) -> Result<()> {
+
+        let seller_trade_state = &mut ctx.accounts.seller_trade_state;
+        let seller = &ctx.accounts.seller;
+        let mint_nft_account = &ctx.accounts.mint_nft_account;
+        let nft_marketplace_account = &ctx.accounts.nft_marketplace_account;
+        let nft_token_account = &ctx.accounts.nft_token_account;
+        let token_mint_account = &ctx.accounts.token_mint_account;
+        let program_as_signer = &ctx.accounts.program_as_signer;
+        let token_program = &ctx.accounts.token_program;
+        let system_program = &ctx.accounts.system_program;
+
+        // Token-2022 NFTs carry their metadata in the mint itself
+        let is_token_2022 = *mint_nft_account.owner == TOKEN_2022_PROGRAM_ID;
+        if is_token_2022 {
+            check_token_2022_nft(mint_nft_account)?;
+        }
+
+        // Token Metadata accounts are only passed for NFTs which may be programmable
+        let programmable_accounts = if is_token_2022 || ctx.remaining_accounts.is_empty() {
+            None
+        } else {
+            Some(ProgrammableNftAccounts::load(ctx.remaining_accounts, mint_nft_account.key, nft_token_account.key)?)
+        };
+        let is_programmable = programmable_accounts.as_ref().map_or(false, |accounts| accounts.is_programmable());
+
+        seller_trade_state.version = SellerTradeState::VERSION;
+        seller_trade_state.list_price = list_price;
+        seller_trade_state.seller = *seller.to_account_info().key;
+        seller_trade_state.mint_nft_account = *mint_nft_account.to_account_info().key;
+        seller_trade_state.nft_marketplace_account = *nft_marketplace_account.to_account_info().key;
+        seller_trade_state.nft_token_account = *nft_token_account.to_account_info().key;
+        seller_trade_state.token_mint_account = *token_mint_account.to_account_info().key;
+        seller_trade_state.bump = *ctx.bumps.get("seller_trade_state").unwrap();
+        seller_trade_state.is_programmable = is_programmable;
+
+        let currency = nft_marketplace_account
+            .get_accepted_currency(token_mint_account.key)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+
+        // USD-pegged listings are settled through the price feed at sale time
+        if let Some(usd_price) = usd_price {
+            let price_feed = ctx.accounts.price_feed.as_ref().ok_or(ErrorCode::PriceFeedNotCorrect)?;
+            let feed = load_price_feed(price_feed, currency)?;
+
+            // Bounds apply to the current value of the USD price in the currency
+            let decimals = Mint::try_from_account_info(token_mint_account)?.decimals;
+            let settlement_amount = feed.get_settlement_amount(usd_price, decimals).ok_or(ErrorCode::PriceNotCorrect)?;
+            require!(currency.is_list_price_allowed(settlement_amount), ErrorCode::ListPriceOutOfBounds);
+
+            seller_trade_state.usd_price = Some(usd_price);
+            seller_trade_state.price_feed = Some(*price_feed.key);
+        }
+        else {
+            require!(currency.is_list_price_allowed(list_price), ErrorCode::ListPriceOutOfBounds);
+        }
+
+        // Private listings can only be bought by the buyer they are reserved for
+        seller_trade_state.reserved_buyer = reserved_buyer;
+        seller_trade_state.token_gate = token_gate;
+        seller_trade_state.claimable_payout = claimable_payout;
+
+        // Holders of the fee tier asset get the fees of their tier when their listing sells
+        seller_trade_state.seller_fee_tier = get_fee_tier_index(
+            nft_marketplace_account,
+            seller.key,
+            ctx.accounts.seller_fee_tier_token_account.as_ref(),
+            ctx.accounts.seller_fee_tier_metadata_account.as_ref(),
+        )?;
+
+        // Listings of verified collection members are counted in the stats of the collection
+        seller_trade_state.collection = count_collection_listing(
+            ctx.accounts.collection_stats.as_ref(),
+            &nft_marketplace_account.key(),
+            mint_nft_account,
+            ctx.accounts.metadata_account.as_ref(),
+            token_mint_account.key,
+            ctx.program_id,
+        )?;
+
+        emit!(ListNftEvent {
+            seller_trade_state: seller_trade_state.key(),
+            seller: seller_trade_state.seller,
+            mint_nft_account: seller_trade_state.mint_nft_account,
+            nft_marketplace_account: seller_trade_state.nft_marketplace_account,
+            token_mint_account: seller_trade_state.token_mint_account,
+            list_price,
+            usd_price,
+            reserved_buyer,
+        });
+
+        if let Some(programmable_accounts) = programmable_accounts.filter(|_| is_programmable) {
+
+            programmable_accounts.delegate_sale(
+                &seller.to_account_info(),
+                nft_token_account,
+                mint_nft_account,
+                &program_as_signer.to_account_info(),
+                &system_program.to_account_info(),
+                token_program,
+            )?;
+
+            return Ok(());
+        }
+
+        approve_token(
+            token_program,
+            &seller.to_account_info(),
+            nft_token_account,
+            &program_as_signer.to_account_info(),
+            1,
+        )?;
+
+        Ok(())
+    }
+
+    /// Delist an NFT, closing its seller trade state and revoking the marketplace delegate if still set.
+    /// Programmable NFTs have their Token Metadata sale delegate revoked, which needs the accounts
+    /// described in `ProgrammableNftAccounts` in `remaining_accounts`.
+    pub fn cancel_listing<'info>(ctx: Context<'_, '_, '_, 'info, CancelListingContext<'info>>) -> Result<()> {
+        let seller_trade_state = &ctx.accounts.seller_trade_state;
+        let seller = &ctx.accounts.seller;
+        let nft_token_account = &ctx.accounts.nft_token_account;
+        let mint_nft_account = &ctx.accounts.mint_nft_account;
+        let program_as_signer = &ctx.accounts.program_as_signer;
+        let token_program = &ctx.accounts.token_program;
+
+        if seller_trade_state.collection.is_some() {
+            let collection_stats = ctx.accounts.collection_stats.as_mut().ok_or(ErrorCode::CollectionStatsNotCorrect)?;
+            collection_stats.active_listings = collection_stats.active_listings.saturating_sub(1);
+        }
+
+        if seller_trade_state.is_programmable {
+            // The sale delegate locks programmable NFTs until Token Metadata revokes it
+            let programmable_accounts = ProgrammableNftAccounts::load(
+                ctx.remaining_accounts,
+                mint_nft_account.key,
+                nft_token_account.key,
+            )?;
+            programmable_accounts.revoke_sale(
+                &seller.to_account_info(),
+                nft_token_account,
+                mint_nft_account,
+                &program_as_signer.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                token_program,
+            )?;
+        }
+        else {
+            // Listings migrated from the unversioned layout are delegated to the legacy program signer
+            let legacy_signer = Pubkey::find_program_address(&[MARKETPLACE, SIGNER], ctx.program_id).0;
+            let is_delegated = TokenAccount::try_from_account_info(nft_token_account).map_or(false, |token_account| {
+                token_account.delegate == COption::Some(program_as_signer.key())
+                    || token_account.delegate == COption::Some(legacy_signer)
+            });
+            if is_delegated {
+                revoke_token(token_program, &seller.to_account_info(), nft_token_account)?;
+            }
+        }
+
+        emit!(CancelListingEvent {
+            seller_trade_state: seller_trade_state.key(),
+            seller: *seller.key,
+            mint_nft_account: seller_trade_state.mint_nft_account,
+            nft_marketplace_account: seller_trade_state.nft_marketplace_account,
+        });
+
+
+        Ok(())
+    }
+
+    /// List many NFTs in one instruction. `remaining_accounts` holds one group per price:
+    ///
+    ///   0. `[writable]` Mint of the NFT
+    ///   1. `[writable]` Seller token account holding the NFT
+    ///   2. `[writable]` Seller trade state to create, derived like in `list_nft_to_marketplace`
+    ///   3. `[]` Token Metadata account of the NFT, any account for Token-2022 NFTs
+    ///   4. `[writable]` Stats address of the verified collection of the NFT, any account outside a collection
+    ///
+    /// Programmable NFTs and USD-pegged prices are not supported, list them one by one instead.
+    pub fn batch_list<'info>(
+        ctx: Context<'_, '_, '_, 'info, BatchListingNftContext<'info>>,
+        prices: Vec<u64>,
+    ) -> Result<()> {
+
+        let nft_marketplace_account = &ctx.accounts.nft_marketplace_account;
+        let token_mint_account = &ctx.accounts.token_mint_account;
+
+        require!(!prices.is_empty() && prices.len() <= MAX_BATCH_LISTING_ITEMS, ErrorCode::BatchListingNotCorrect);
+        require!(
+            ctx.remaining_accounts.len() == prices.len() * BATCH_LISTING_ACCOUNTS_LEN,
+            ErrorCode::BatchListingNotCorrect
+        );
+
+        let currency = nft_marketplace_account
+            .get_accepted_currency(token_mint_account.key)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+
+        let seller_fee_tier = get_fee_tier_index(
+            nft_marketplace_account,
+            ctx.accounts.seller.key,
+            ctx.accounts.seller_fee_tier_token_account.as_ref(),
+            ctx.accounts.seller_fee_tier_metadata_account.as_ref(),
+        )?;
+
+        let rent = Rent::get()?;
+
+        for (index, (list_price, accounts)) in prices.iter()
+            .zip(ctx.remaining_accounts.chunks(BATCH_LISTING_ACCOUNTS_LEN))
+            .enumerate()
+        {
+            if let Err(error) = list_batch_item(ctx.accounts, currency, *list_price, seller_fee_tier, accounts, &rent, ctx.program_id) {
+                msg!("Batch listing failed at item {}", index);
+                return Err(error);
+            }
+        }
+
+
+        Ok(())
+    }
+
+    /// Buy a listing. `amount` is the listing price, or the most the buyer accepts to pay for a
+    /// USD-pegged listing, and the taker fee of the currency is charged on top of it.
+    /// `royalty_basis_points` is the share of the creator royalty the buyer chooses to pay,
+    /// clamped by the royalty policy of the marketplace. The share the policy enforces is taken out
+    /// of the seller proceeds, the buyer pays any share above it on top of the price.
+    /// The currency token accounts of the royalty recipients are passed last in `remaining_accounts`.
+    ///
+    /// When the seller listed with `claimable_payout`, the seller proceeds and royalties are paid into the vault
+    /// and credited to claimable balances instead, withdrawn later with `claim`. The claimable balance
+    /// accounts of the royalty recipients are then passed last in place of their token accounts.
+    ///
+    /// Sales of verified collection members pass the stats address of the collection, and its price observations
+    /// when the collection has stats, so every sale of the collection is recorded.
+    ///
+    /// The seller trade state is closed to the seller once the listing is bought.
+    pub fn execute_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyNftContext<'info>>,
+        amount: u64,
+        royalty_basis_points: Option<u16>,
+    ) -> Result<()> {
+        let buyer = &ctx.accounts.buyer;
+        let seller = &ctx.accounts.seller;
+        let buyer_nft_account = &ctx.accounts.buyer_nft_account;
+        let buyer_token_account = &ctx.accounts.buyer_token_account;
+        let seller_trade_state = &ctx.accounts.seller_trade_state;
+        let mint_nft_account = &ctx.accounts.mint_nft_account;
+        let nft_marketplace_account = &ctx.accounts.nft_marketplace_account;
+        let nft_token_account = &ctx.accounts.nft_token_account;
+        let token_mint_account = &ctx.accounts.token_mint_account;
+        let program_as_signer = &ctx.accounts.program_as_signer;
+        // let authority = &ctx.accounts.authority;
+        let fee_account = &mut ctx.accounts.fee_account;
+        let token_program = &ctx.accounts.token_program;
+        let system_program = &ctx.accounts.system_program;
+        let currency_token_program = &ctx.accounts.currency_token_program;
+
+        require!(seller_trade_state.seller == *seller.to_account_info().key, ErrorCode::SellerNotCorrect);
+        require!(seller_trade_state.mint_nft_account == *mint_nft_account.to_account_info().key, ErrorCode::MintNFTAccountNotCorrect);
+        require!(seller_trade_state.nft_marketplace_account == *nft_marketplace_account.to_account_info().key, ErrorCode::NFTMarketplaceAccountNotCorrect);
+        require!(seller_trade_state.nft_token_account == *nft_token_account.to_account_info().key, ErrorCode::NFTTokenAccountNotCorrect);
+        require!(seller_trade_state.token_mint_account == *token_mint_account.to_account_info().key, ErrorCode::TokenMintAccountNotCorrect);
+
+        // A listing gate overrides the gate of the marketplace
+        if let Some(token_gate) = seller_trade_state.token_gate.as_ref().or(nft_marketplace_account.token_gate.as_ref()) {
+            check_token_gate(
+                token_gate,
+                buyer.key,
+                ctx.accounts.gate_token_account.as_ref(),
+                ctx.accounts.gate_metadata_account.as_ref(),
+            )?;
+        }
+
+        let decimals = Mint::try_from_account_info(token_mint_account)?.decimals;
+        let currency = nft_marketplace_account
+            .get_accepted_currency(token_mint_account.key)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+
+        // USD-pegged listings settle at the feed price, `amount` is the most the buyer pays
+        let sale_price = match seller_trade_state.usd_price {
+            Some(usd_price) => {
+                let price_feed = ctx.accounts.price_feed.as_ref().ok_or(ErrorCode::PriceFeedNotCorrect)?;
+                require!(seller_trade_state.price_feed == Some(*price_feed.key), ErrorCode::PriceFeedNotCorrect);
+
+                let feed = load_price_feed(price_feed, currency)?;
+                require!(feed.is_fresh(Clock::get()?.unix_timestamp, MAX_PRICE_FEED_AGE), ErrorCode::PriceFeedStale);
+                require!(feed.is_confident(MAX_PRICE_FEED_CONFIDENCE_BASIS_POINTS), ErrorCode::PriceFeedNotConfident);
+
+                let settlement_amount = feed.get_settlement_amount(usd_price, decimals).ok_or(ErrorCode::PriceNotCorrect)?;
+                require!(settlement_amount <= amount, ErrorCode::PriceNotCorrect);
+                settlement_amount
+            }
+            None => {
+                require!(seller_trade_state.list_price == amount, ErrorCode::PriceNotCorrect);
+                amount
+            }
+        };
+
+
+        // Holders of the fee tier asset get the fees of their tier when lower,
+        // the seller the tier they held the asset of when listing
+        let mut currency = currency.clone();
+        let mut seller_fee_tier = None;
+        if let Some(tier) = nft_marketplace_account.get_fee_tier_at(seller_trade_state.seller_fee_tier) {
+            currency.seller_fee_basis_points = currency.seller_fee_basis_points.min(tier.seller_fee_basis_points);
+            seller_fee_tier = seller_trade_state.seller_fee_tier;
+        }
+        let buyer_fee_tier = get_fee_tier_index(
+            nft_marketplace_account,
+            buyer.key,
+            ctx.accounts.buyer_fee_tier_token_account.as_ref(),
+            ctx.accounts.buyer_fee_tier_metadata_account.as_ref(),
+        )?;
+        if let Some(tier) = nft_marketplace_account.get_fee_tier_at(buyer_fee_tier) {
+            currency.buyer_fee_basis_points = currency.buyer_fee_basis_points.min(tier.buyer_fee_basis_points);
+        }
+
+        // The maker fee is taken from the seller proceeds, the taker fee is charged on top of the price
+        let quote = currency.get_fee_quote(sale_price).ok_or(ErrorCode::PriceNotCorrect)?;
+        let fee = quote.maker_fee + quote.taker_fee;
+
+
+        // Creators are paid the share of the royalty allowed by the royalty policy
+        let royalty_share = nft_marketplace_account.royalty_policy.get_royalty_share(royalty_basis_points);
+        let mut royalty = load_royalty(mint_nft_account, ctx.accounts.metadata_account.as_ref())?;
+        royalty.apply_share(royalty_share);
+        let royalty_accounts_len = royalty.get_amounts(sale_price).iter().filter(|amount| **amount > 0).count();
+        require!(ctx.remaining_accounts.len() >= royalty_accounts_len, ErrorCode::RoyaltyAccountNotCorrect);
+        let (nft_accounts, royalty_accounts) = ctx.remaining_accounts.split_at(ctx.remaining_accounts.len() - royalty_accounts_len);
+
+        let nft_marketplace_key = nft_marketplace_account.key();
+        let seed : &[&[u8]] = &[
+            MARKETPLACE,
+            nft_marketplace_key.as_ref(),
+            SIGNER,
+            &[nft_marketplace_account.signer_bump]
+        ];
+
+        // Transfer NFT to buyer
+        if seller_trade_state.is_programmable {
+            let programmable_accounts = ProgrammableNftAccounts::load(
+                nft_accounts,
+                mint_nft_account.key,
+                nft_token_account.key,
+            )?;
+
+            programmable_accounts.transfer(
+                program_as_signer,
+                &buyer.to_account_info(),
+                nft_token_account,
+                seller,
+                buyer_nft_account,
+                &buyer.to_account_info(),
+                mint_nft_account,
+                &system_program.to_account_info(),
+                token_program,
+                &[seed],
+            )?;
+        }
+        else if *mint_nft_account.owner == TOKEN_2022_PROGRAM_ID {
+            // Extra accounts of the transfer hook program, if any, are passed through
+            transfer_token_checked(
+                token_program,
+                program_as_signer,
+                nft_token_account,
+                mint_nft_account,
+                buyer_nft_account,
+                1,
+                0,
+                nft_accounts,
+                &[seed],
+            )?;
+        }
+        else {
+            transfer_token(
+                program_as_signer,
+                nft_token_account,
+                buyer_nft_account,
+                1,
+                &[seed],
+            )?;
+        }
+
+        let epoch = Clock::get()?.epoch;
+
+        // The seller chose when listing whether the proceeds are paid out or credited in the vault
+        let vault_token_account = if seller_trade_state.claimable_payout {
+            Some(ctx.accounts.vault_token_account.as_ref().ok_or(ErrorCode::VaultAccountNotCorrect)?)
+        } else {
+            None
+        };
+
+        // Token-2022 mints may withhold a transfer fee from each leg
+        let total_royalty = match vault_token_account {
+            // Credit royalty to creators, paid into the vault with the seller proceeds
+            Some(_) => credit_royalty(
+                &royalty,
+                sale_price,
+                royalty_accounts,
+                &nft_marketplace_account.key(),
+                token_mint_account,
+                buyer,
+                ctx.program_id,
+                epoch,
+            )?,
+            // Transfer royalty to creators
+            None => transfer_royalty(
+                &royalty,
+                sale_price,
+                royalty_accounts,
+                currency_token_program,
+                buyer,
+                buyer_token_account,
+                token_mint_account,
+                decimals,
+            )?,
+        };
+
+
+        // The seller pays the royalty the policy enforces, the buyer the share chosen above it
+        let seller_royalty = nft_marketplace_account.royalty_policy.get_seller_royalty(total_royalty, royalty_share);
+        let seller_amount = quote.seller_receives.checked_sub(seller_royalty).ok_or(ErrorCode::PriceNotCorrect)?;
+        let seller_transfer_fee = calculate_transfer_fee(token_mint_account, seller_amount, epoch)?;
+
+        // The referrer share is taken out of the fee, so the buyer never pays more
+        let referrer_token_account = ctx.accounts.referrer_token_account.as_ref();
+        let referral_fee = match referrer_token_account {
+            Some(_) => nft_marketplace_account.get_referral_fee(fee).ok_or(ErrorCode::PriceNotCorrect)?,
+            None => 0,
+        };
+        let marketplace_fee = fee - referral_fee;
+        let fee_transfer_fee = calculate_transfer_fee(token_mint_account, marketplace_fee, epoch)?;
+
+        if let Some(vault_token_account) = vault_token_account {
+            // Credit seller and pay the seller proceeds and royalty into the vault
+            let seller_claimable_balance = ctx.accounts.seller_claimable_balance.as_ref().ok_or(ErrorCode::ClaimableBalanceNotCorrect)?;
+            credit_claimable_balance(
+                seller_claimable_balance,
+                seller.key,
+                &nft_marketplace_account.key(),
+                token_mint_account.key,
+                seller_amount - seller_transfer_fee,
+                buyer,
+                ctx.program_id,
+            )?;
+
+            transfer_token_checked(
+                currency_token_program,
+                buyer,
+                buyer_token_account,
+                token_mint_account,
+                vault_token_account,
+                seller_amount + total_royalty,
+                decimals,
+                &[],
+                &[]
+            )?;
+
+        }
+        else {
+            // Transfer token to seller
+            let seller_token_account = ctx.accounts.seller_token_account.as_ref().ok_or(ErrorCode::SellerTokenAccountNotCorrect)?;
+            transfer_token_checked(
+                currency_token_program,
+                buyer,
+                buyer_token_account,
+                token_mint_account,
+                seller_token_account,
+                seller_amount,
+                decimals,
+                &[],
+                &[]
+            )?;
+
+        }
+        msg!("DEBUG: fee {:?}", fee);
+
+        // Transfer fee to fee account
+        transfer_token_checked(
+            currency_token_program,
+            buyer,
+            buyer_token_account,
+            token_mint_account,
+            fee_account,
+            marketplace_fee,
+            decimals,
+            &[],
+            &[]
+        )?;
+
+
+        // Transfer referral fee to referrer
+        if let Some(referrer_token_account) = referrer_token_account.filter(|_| referral_fee > 0) {
+            transfer_token_checked(
+                currency_token_program,
+                buyer,
+                buyer_token_account,
+                token_mint_account,
+                referrer_token_account,
+                referral_fee,
+                decimals,
+                &[],
+                &[]
+            )?;
+
+        }
+
+        // Sales of verified collection members are recorded in the stats of the collection,
+        // which must be passed whenever the collection has stats
+        record_collection_sale(
+            ctx.accounts.collection_stats.as_ref(),
+            ctx.accounts.price_observations.as_ref(),
+            &nft_marketplace_account.key(),
+            mint_nft_account,
+            ctx.accounts.metadata_account.as_ref(),
+            token_mint_account.key,
+            seller_trade_state.collection,
+            sale_price,
+            ctx.program_id,
+        )?;
+
+        emit!(SaleEvent {
+            seller_trade_state: seller_trade_state.key(),
+            seller: *seller.key,
+            buyer: *buyer.key,
+            mint_nft_account: *mint_nft_account.key,
+            nft_marketplace_account: nft_marketplace_account.key(),
+            token_mint_account: *token_mint_account.key,
+            sale_price,
+            maker_fee: quote.maker_fee,
+            taker_fee: quote.taker_fee,
+            royalty: total_royalty,
+            referrer: match referrer_token_account {
+                Some(referrer_token_account) => Some(TokenAccount::try_from_account_info(referrer_token_account)?.owner),
+                None => None,
+            },
+            referral_fee,
+            seller_fee_tier,
+            buyer_fee_tier,
+        });
+
+        ctx.accounts.nft_marketplace_account
+            .record_sales(token_mint_account.key, 1, sale_price, marketplace_fee - fee_transfer_fee)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+
+        Ok(())
+
+    }
+
+    /// Buy many fixed-price listings of one marketplace and currency in one instruction.
+    /// `remaining_accounts` holds one group per expected price:
+    ///
+    ///   0. `[writable]` Seller of the listing
+    ///   1. `[writable]` Seller trade state of the listing
+    ///   2. `[writable]` Mint of the NFT
+    ///   3. `[writable]` Seller token account holding the NFT
+    ///   4. `[writable]` Buyer token account to receive the NFT
+    ///   5. `[writable]` Seller token account to receive the currency
+    ///   6. `[]` Token Metadata account of the NFT (the mint for Token-2022 NFTs)
+    ///   7. `[writable]` Stats address of the verified collection of the NFT, any account outside a collection
+    ///   8. `[writable]` Price observations of the collection stats, any account if the collection has no stats
+    ///
+    /// followed by the currency token account of each royalty recipient. Sweeps pay the full royalty,
+    /// the seller the share the royalty policy enforces and the buyer the rest on top of the price.
+    ///
+    /// Listings which were bought, delisted, reserved for another buyer, gated, USD-pegged, programmable
+    /// or paying into the vault cannot be swept.
+    /// They abort the sweep unless `skip_unavailable` is set, in which case they are left out, as are listings
+    /// relisted at another price than expected. The listings filled are closed to their sellers.
+    /// The sweep aborts if the total paid, taker fees included, would be more than `max_total`.
+    pub fn sweep<'info>(
+        ctx: Context<'_, '_, '_, 'info, SweepContext<'info>>,
+        prices: Vec<u64>,
+        max_total: u64,
+        skip_unavailable: bool,
+    ) -> Result<()> {
+        let buyer = &ctx.accounts.buyer;
+        let buyer_token_account = &ctx.accounts.buyer_token_account;
+        let nft_marketplace_account = &ctx.accounts.nft_marketplace_account;
+        let token_mint_account = &ctx.accounts.token_mint_account;
+        let fee_account = &ctx.accounts.fee_account;
+        let currency_token_program = &ctx.accounts.currency_token_program;
+
+        // Gate proofs cannot be passed to a sweep
+        require!(nft_marketplace_account.token_gate.is_none(), ErrorCode::TokenGateNotMet);
+        require!(!prices.is_empty() && prices.len() <= MAX_SWEEP_ITEMS, ErrorCode::SweepNotCorrect);
+        let items_accounts_len = prices.len() * SWEEP_ACCOUNTS_LEN;
+        require!(ctx.remaining_accounts.len() >= items_accounts_len, ErrorCode::SweepNotCorrect);
+        let (items_accounts, royalty_accounts) = ctx.remaining_accounts.split_at(items_accounts_len);
+
+        let decimals = Mint::try_from_account_info(token_mint_account)?.decimals;
+        let currency = nft_marketplace_account
+            .get_accepted_currency(token_mint_account.key)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+        let epoch = Clock::get()?.epoch;
+
+        let nft_marketplace_key = nft_marketplace_account.key();
+        let seed : &[&[u8]] = &[
+            MARKETPLACE,
+            nft_marketplace_key.as_ref(),
+            SIGNER,
+            &[nft_marketplace_account.signer_bump]
+        ];
+
+        let mut total: u64 = 0;
+        let mut total_fee: u64 = 0;
+        let mut volume: u64 = 0;
+        let mut filled: usize = 0;
+
+        for (price, accounts) in prices.iter().zip(items_accounts.chunks(SWEEP_ACCOUNTS_LEN)) {
+            let quote = sweep_listing(
+                ctx.accounts,
+                currency,
+                *price,
+                accounts,
+                royalty_accounts,
+                decimals,
+                seed,
+                skip_unavailable,
+                ctx.program_id,
+            )?;
+
+            match quote {
+                Some(quote) => {
+                    total = total.checked_add(quote.buyer_pays).ok_or(ErrorCode::SweepSpendCapExceeded)?;
+                    require!(total <= max_total, ErrorCode::SweepSpendCapExceeded);
+                    total_fee += quote.maker_fee + quote.taker_fee;
+                    volume += quote.price;
+                    filled += 1;
+                }
+                None if skip_unavailable => {}
+                None => return err!(ErrorCode::ListingNotAvailable),
+            }
+        }
+
+        // Transfer the fees of every filled listing to fee account at once
+        if total_fee > 0 {
+            transfer_token_checked(
+                currency_token_program,
+                buyer,
+                buyer_token_account,
+                token_mint_account,
+                fee_account,
+                total_fee,
+                decimals,
+                &[],
+                &[]
+            )?;
+        }
+
+        let fee_transfer_fee = calculate_transfer_fee(token_mint_account, total_fee, epoch)?;
+
+        ctx.accounts.nft_marketplace_account
+            .record_sales(token_mint_account.key, filled as u64, volume, total_fee - fee_transfer_fee)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+
+        Ok(())
+    }
+
+    /// List several NFTs of one seller as a single lot. `remaining_accounts` holds one group per NFT:
+    ///
+    ///   0. `[writable]` Mint of the NFT
+    ///   1. `[writable]` Seller token account holding the NFT
+    ///   2. `[]` Token Metadata account of the NFT, any account for Token-2022 NFTs
+    ///   3. `[writable]` Stats address of the verified collection of the NFT, any account outside a collection
+    pub fn list_bundle<'info>(
+        ctx: Context<'_, '_, '_, 'info, ListBundleContext<'info>>,
+        bundle_id: u64,
+        price: u64,
+    ) -> Result<()> {
+
+        let bundle_listing = &mut ctx.accounts.bundle_listing;
+        let seller = &ctx.accounts.seller;
+        let nft_marketplace_account = &ctx.accounts.nft_marketplace_account;
+        let token_mint_account = &ctx.accounts.token_mint_account;
+        let program_as_signer = &ctx.accounts.program_as_signer;
+        let token_program = &ctx.accounts.token_program;
+
+        let currency = nft_marketplace_account
+            .get_accepted_currency(token_mint_account.key)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+        require!(currency.is_list_price_allowed(price), ErrorCode::ListPriceOutOfBounds);
+
+        require!(ctx.remaining_accounts.len() % BUNDLE_LISTING_ACCOUNTS_LEN == 0, ErrorCode::BundleNotCorrect);
+        let items_len = ctx.remaining_accounts.len() / BUNDLE_LISTING_ACCOUNTS_LEN;
+        require!((2..=MAX_BUNDLE_ITEMS).contains(&items_len), ErrorCode::BundleNotCorrect);
+
+        let mut items: Vec<BundleItem> = Vec::with_capacity(items_len);
+        for accounts in ctx.remaining_accounts.chunks(BUNDLE_LISTING_ACCOUNTS_LEN) {
+            let mint_nft_account = &accounts[0];
+            let nft_token_account = &accounts[1];
+            let metadata_account = &accounts[2];
+            let collection_stats = &accounts[3];
+
+            require!(*mint_nft_account.owner == *token_program.key, ErrorCode::TokenProgramNotCorrect);
+            require!(items.iter().all(|item| item.mint_nft_account != *mint_nft_account.key), ErrorCode::BundleNotCorrect);
+            if *mint_nft_account.owner == TOKEN_2022_PROGRAM_ID {
+                check_token_2022_nft(mint_nft_account)?;
+                // Transfer hook accounts are not passed to bundle sales
+                require!(
+                    get_transfer_hook_program_id(&mint_nft_account.try_borrow_data()?)?.is_none(),
+                    ErrorCode::MintExtensionNotSupported
+                );
+            }
+
+            let token_account = TokenAccount::try_from_account_info(nft_token_account)?;
+            require!(token_account.owner == *seller.key, ErrorCode::NFTTokenAccountNotCorrect);
+            require!(token_account.mint == *mint_nft_account.key, ErrorCode::MintNFTAccountNotCorrect);
+            require!(token_account.amount == 1, ErrorCode::NFTTokenAccountNotCorrect);
+
+            approve_token(
+                token_program,
+                &seller.to_account_info(),
+                nft_token_account,
+                &program_as_signer.to_account_info(),
+                1,
+            )?;
+
+            let collection = count_collection_listing(
+                Some(collection_stats),
+                &nft_marketplace_account.key(),
+                mint_nft_account,
+                Some(metadata_account),
+                token_mint_account.key,
+                ctx.program_id,
+            )?;
+
+            items.push(BundleItem {
+                mint_nft_account: *mint_nft_account.key,
+                nft_token_account: *nft_token_account.key,
+                collection,
+            });
+        }
+
+        bundle_listing.seller = *seller.key;
+        bundle_listing.bump = *ctx.bumps.get("bundle_listing").unwrap();
+        bundle_listing.bundle_id = bundle_id;
+        bundle_listing.price = price;
+        bundle_listing.nft_marketplace_account = nft_marketplace_account.key();
+        bundle_listing.token_mint_account = *token_mint_account.key;
+        bundle_listing.items = items;
+
+
+        Ok(())
+    }
+
+    /// Buy every NFT of a bundle. `remaining_accounts` holds one group per NFT, in listing order:
+    ///
+    ///   0. `[writable]` Mint of the NFT
+    ///   1. `[writable]` Seller token account holding the NFT
+    ///   2. `[writable]` Buyer token account to receive the NFT
+    ///   3. `[]` Token Metadata account of the NFT (the mint for Token-2022 NFTs)
+    ///   4. `[writable]` Stats address of the verified collection of the NFT, any account outside a collection
+    ///   5. `[writable]` Price observations of the collection stats, any account if the collection has no stats
+    ///
+    /// followed by the currency token account of each royalty recipient. The bundle price is split evenly between the NFTs and each
+    /// NFT pays its own royalty on its part. The taker fee is charged on top of the bundle price.
+    pub fn execute_bundle_sale<'info>(ctx: Context<'_, '_, '_, 'info, BuyBundleContext<'info>>, amount: u64) -> Result<()> {
+        let buyer = &ctx.accounts.buyer;
+        let buyer_token_account = &ctx.accounts.buyer_token_account;
+        let seller_token_account = &ctx.accounts.seller_token_account;
+        let bundle_listing = &ctx.accounts.bundle_listing;
+        let nft_marketplace_account = &ctx.accounts.nft_marketplace_account;
+        let token_mint_account = &ctx.accounts.token_mint_account;
+        let program_as_signer = &ctx.accounts.program_as_signer;
+        let fee_account = &ctx.accounts.fee_account;
+        let token_program = &ctx.accounts.token_program;
+        let currency_token_program = &ctx.accounts.currency_token_program;
+
+        require!(bundle_listing.price == amount, ErrorCode::PriceNotCorrect);
+        // Gate proofs cannot be passed to a bundle sale
+        require!(nft_marketplace_account.token_gate.is_none(), ErrorCode::TokenGateNotMet);
+
+        let items_len = bundle_listing.items.len();
+        let items_accounts_len = items_len * BUNDLE_SALE_ACCOUNTS_LEN;
+        require!(ctx.remaining_accounts.len() >= items_accounts_len, ErrorCode::BundleNotCorrect);
+        let (items_accounts, royalty_accounts) = ctx.remaining_accounts.split_at(items_accounts_len);
+
+        let decimals = Mint::try_from_account_info(token_mint_account)?.decimals;
+        let currency = nft_marketplace_account
+            .get_accepted_currency(token_mint_account.key)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+        let quote = currency.get_fee_quote(amount).ok_or(ErrorCode::PriceNotCorrect)?;
+        let fee = quote.maker_fee + quote.taker_fee;
+
+        let nft_marketplace_key = nft_marketplace_account.key();
+        let seed : &[&[u8]] = &[
+            MARKETPLACE,
+            nft_marketplace_key.as_ref(),
+            SIGNER,
+            &[nft_marketplace_account.signer_bump]
+        ];
+
+        let mut total_royalty: u64 = 0;
+        for (index, (item, accounts)) in bundle_listing.items.iter()
+            .zip(items_accounts.chunks(BUNDLE_SALE_ACCOUNTS_LEN))
+            .enumerate()
+        {
+            let mint_nft_account = &accounts[0];
+            let nft_token_account = &accounts[1];
+            let buyer_nft_account = &accounts[2];
+            let metadata_account = &accounts[3];
+            let collection_stats = &accounts[4];
+            let price_observations = &accounts[5];
+
+            require!(item.mint_nft_account == *mint_nft_account.key, ErrorCode::MintNFTAccountNotCorrect);
+            require!(item.nft_token_account == *nft_token_account.key, ErrorCode::NFTTokenAccountNotCorrect);
+            require!(*mint_nft_account.owner == *token_program.key, ErrorCode::TokenProgramNotCorrect);
+
+            // Transfer NFT to buyer
+            if *mint_nft_account.owner == TOKEN_2022_PROGRAM_ID {
+                transfer_token_checked(
+                    token_program,
+                    program_as_signer,
+                    nft_token_account,
+                    mint_nft_account,
+                    buyer_nft_account,
+                    1,
+                    0,
+                    &[],
+                    &[seed],
+                )?;
+            }
+            else {
+                transfer_token(
+                    program_as_signer,
+                    nft_token_account,
+                    buyer_nft_account,
+                    1,
+                    &[seed],
+                )?;
+            }
+
+            // The last NFT takes the rounding dust of the split
+            let item_price = if index == items_len - 1 {
+                amount - amount / items_len as u64 * (items_len as u64 - 1)
+            } else {
+                amount / items_len as u64
+            };
+
+            // Transfer royalty to creators
+            let royalty = load_royalty(mint_nft_account, Some(metadata_account))?;
+            total_royalty += transfer_royalty(
+                &royalty,
+                item_price,
+                royalty_accounts,
+                currency_token_program,
+                buyer,
+                buyer_token_account,
+                token_mint_account,
+                decimals,
+            )?;
+
+            record_collection_sale(
+                Some(collection_stats),
+                Some(price_observations),
+                &nft_marketplace_key,
+                mint_nft_account,
+                Some(metadata_account),
+                token_mint_account.key,
+                item.collection,
+                item_price,
+                ctx.program_id,
+            )?;
+        }
+
+
+        // Transfer token to seller
+        let seller_amount = quote.seller_receives
+            .checked_sub(total_royalty)
+            .ok_or(ErrorCode::PriceNotCorrect)?;
+        transfer_token_checked(
+            currency_token_program,
+            buyer,
+            buyer_token_account,
+            token_mint_account,
+            seller_token_account,
+            seller_amount,
+            decimals,
+            &[],
+            &[]
+        )?;
+
+        // Transfer fee to fee account
+        transfer_token_checked(
+            currency_token_program,
+            buyer,
+            buyer_token_account,
+            token_mint_account,
+            fee_account,
+            fee,
+            decimals,
+            &[],
+            &[]
+        )?;
+
+
+        let fee_transfer_fee = calculate_transfer_fee(token_mint_account, fee, Clock::get()?.epoch)?;
+        ctx.accounts.nft_marketplace_account
+            .record_sales(token_mint_account.key, items_len as u64, amount, fee - fee_transfer_fee)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+
+        Ok(())
+    }
+
+    /// Withdraw a bundle from sale and revoke the delegation of its NFTs.
+    /// `remaining_accounts` holds one group per NFT, in listing order:
+    ///
+    ///   0. `[writable]` Seller token account holding the NFT
+    ///   1. `[writable]` Stats of the collection counting the NFT, any account if not counted
+    pub fn cancel_bundle<'info>(ctx: Context<'_, '_, '_, 'info, CancelBundleContext<'info>>) -> Result<()> {
+
+        let bundle_listing = &ctx.accounts.bundle_listing;
+        let seller = &ctx.accounts.seller;
+        let program_as_signer = &ctx.accounts.program_as_signer;
+        let token_program = &ctx.accounts.token_program;
+
+        require!(
+            ctx.remaining_accounts.len() == bundle_listing.items.len() * CANCEL_BUNDLE_ACCOUNTS_LEN,
+            ErrorCode::BundleNotCorrect
+        );
+
+        for (item, accounts) in bundle_listing.items.iter().zip(ctx.remaining_accounts.chunks(CANCEL_BUNDLE_ACCOUNTS_LEN)) {
+            let nft_token_account = &accounts[0];
+            let collection_stats = &accounts[1];
+
+            require!(*nft_token_account.key == item.nft_token_account, ErrorCode::NFTTokenAccountNotCorrect);
+            require!(*nft_token_account.owner == *token_program.key, ErrorCode::TokenProgramNotCorrect);
+
+            // NFTs moved out of the seller token account since listing are left as they are
+            let is_delegated = TokenAccount::try_from_account_info(nft_token_account).map_or(false, |token_account| {
+                token_account.owner == *seller.key
+                    && token_account.delegate == COption::Some(program_as_signer.key())
+            });
+            if is_delegated {
+                revoke_token(token_program, &seller.to_account_info(), nft_token_account)?;
+            }
+
+            // NFTs counted in the stats of their collection leave its active listings
+            if let Some(collection) = item.collection {
+                let stats = load_collection_stats(
+                    Some(collection_stats),
+                    &bundle_listing.nft_marketplace_account,
+                    &collection,
+                    &bundle_listing.token_mint_account,
+                    ctx.program_id,
+                )?;
+                if let Some(mut stats) = stats {
+                    stats.active_listings = stats.active_listings.saturating_sub(1);
+                    stats.exit(ctx.program_id)?;
+                }
+            }
+        }
+
+
+        Ok(())
+    }
+
+    /// Escrow the `offered` assets until `taker` sends the `requested` assets back.
+    /// `remaining_accounts` holds one pair per offered asset: the maker token account holding it,
+    /// then the escrow token account owned by the swap account.
+    pub fn create_swap<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateSwapContext<'info>>,
+        swap_id: u64,
+        taker: Pubkey,
+        offered: Vec<SwapAsset>,
+        requested: Vec<SwapAsset>,
+    ) -> Result<()> {
+
+        let swap = &mut ctx.accounts.swap;
+        let maker = &ctx.accounts.maker;
+
+        require!(taker != *maker.key, ErrorCode::SwapNotCorrect);
+        require!(!offered.is_empty() && offered.len() <= MAX_SWAP_ASSETS, ErrorCode::SwapNotCorrect);
+        require!(!requested.is_empty() && requested.len() <= MAX_SWAP_ASSETS, ErrorCode::SwapNotCorrect);
+        require!(offered.iter().chain(requested.iter()).all(|asset| asset.amount > 0), ErrorCode::SwapNotCorrect);
+        require!(
+            ctx.remaining_accounts.len() == offered.len() * SWAP_LEG_ACCOUNTS_LEN,
+            ErrorCode::SwapNotCorrect
+        );
+
+        for (asset, legs) in offered.iter().zip(ctx.remaining_accounts.chunks(SWAP_LEG_ACCOUNTS_LEN)) {
+            let maker_token_account = &legs[0];
+            let escrow_token_account = &legs[1];
+
+            let escrow = TokenAccount::try_from_account_info(escrow_token_account)?;
+            require!(escrow.owner == swap.key(), ErrorCode::SwapTokenAccountNotCorrect);
+            require!(escrow.mint == asset.mint, ErrorCode::SwapTokenAccountNotCorrect);
+
+            transfer_token(
+                &maker.to_account_info(),
+                maker_token_account,
+                escrow_token_account,
+                asset.amount,
+                &[],
+            )?;
+        }
+
+        swap.maker = *maker.key;
+        swap.taker = taker;
+        swap.bump = *ctx.bumps.get("swap").unwrap();
+        swap.swap_id = swap_id;
+        swap.offered = offered;
+        swap.requested = requested;
+
+
+        Ok(())
+    }
+
+    /// Complete a swap as the named taker, closing the escrow token accounts to the maker.
+    /// `remaining_accounts` holds one pair per requested asset:
+    /// the taker token account holding it, then the maker token account receiving it; followed by
+    /// one pair per offered asset: the escrow token account, then the taker token account receiving it.
+    pub fn accept_swap<'info>(ctx: Context<'_, '_, '_, 'info, AcceptSwapContext<'info>>) -> Result<()> {
+
+        let swap = &ctx.accounts.swap;
+        let maker = &ctx.accounts.maker;
+        let taker = &ctx.accounts.taker;
+
+        let requested_accounts_len = swap.requested.len() * SWAP_LEG_ACCOUNTS_LEN;
+        require!(ctx.remaining_accounts.len() >= requested_accounts_len, ErrorCode::SwapNotCorrect);
+        let (requested_accounts, offered_accounts) = ctx.remaining_accounts.split_at(requested_accounts_len);
+
+        for (asset, legs) in swap.requested.iter().zip(requested_accounts.chunks(SWAP_LEG_ACCOUNTS_LEN)) {
+            let taker_token_account = &legs[0];
+            let maker_token_account = &legs[1];
+
+            let destination = TokenAccount::try_from_account_info(maker_token_account)?;
+            require!(destination.owner == *maker.key, ErrorCode::SwapTokenAccountNotCorrect);
+            require!(destination.mint == asset.mint, ErrorCode::SwapTokenAccountNotCorrect);
+
+            transfer_token(
+                &taker.to_account_info(),
+                taker_token_account,
+                maker_token_account,
+                asset.amount,
+                &[],
+            )?;
+        }
+
+        release_swap_escrow(swap, maker, &ctx.accounts.token_program, offered_accounts)?;
+
+
+        Ok(())
+    }
+
+    /// Refund the escrowed assets to the maker and close the swap and its escrow token accounts.
+    /// `remaining_accounts` holds one pair per offered asset: the escrow token account,
+    /// then the maker token account receiving it back.
+    pub fn cancel_swap<'info>(ctx: Context<'_, '_, '_, 'info, CancelSwapContext<'info>>) -> Result<()> {
+
+        let swap = &ctx.accounts.swap;
+
+        release_swap_escrow(
+            swap,
+            &ctx.accounts.maker.to_account_info(),
+            &ctx.accounts.token_program,
+            ctx.remaining_accounts,
+        )?;
+
+
+        Ok(())
+    }
+
+    /// Withdraw `amount` of the balance credited to the wallet by sales of the marketplace
+    pub fn claim(ctx: Context<ClaimContext>, amount: u64) -> Result<()> {
+        let claimable_balance = &mut ctx.accounts.claimable_balance;
+        let wallet = &ctx.accounts.wallet;
+        let vault_token_account = &ctx.accounts.vault_token_account;
+        let destination_account = &ctx.accounts.destination_account;
+        let token_mint_account = &ctx.accounts.token_mint_account;
+        let program_as_signer = &ctx.accounts.program_as_signer;
+        let token_program = &ctx.accounts.token_program;
+
+        require!(amount <= claimable_balance.amount, ErrorCode::ClaimableBalanceNotEnough);
+        claimable_balance.amount -= amount;
+
+        let decimals = Mint::try_from_account_info(token_mint_account)?.decimals;
+
+        let seed : &[&[u8]] = &[
+            MARKETPLACE,
+            claimable_balance.nft_marketplace_account.as_ref(),
+            SIGNER,
+            &[ctx.accounts.nft_marketplace_account.signer_bump]
+        ];
+
+        // Transfer claimed balance from the vault
+        transfer_token_checked(
+            token_program,
+            program_as_signer,
+            vault_token_account,
+            token_mint_account,
+            destination_account,
+            amount,
+            decimals,
+            &[],
+            &[seed]
+        )?;
+
+
+        emit!(ClaimEvent {
+            claimable_balance: claimable_balance.key(),
+            wallet: *wallet.key,
+            nft_marketplace_account: claimable_balance.nft_marketplace_account,
+            token_mint_account: *token_mint_account.key,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Withdraw `amount` from the fee account of an accepted currency, booked in its accounting.
+    /// Fees of marketplaces with fee splits are paid out with `distribute_fees` instead.
+    pub fn withdraw_fee(ctx: Context<WithdrawFromFeeAccountContext>, amount: u64) -> Result<()> {
+        let nft_marketplace_account = &mut ctx.accounts.nft_marketplace_account;
+        let fee_account = &ctx.accounts.fee_account;
+        let owner = &ctx.accounts.owner;
+        let destination_account = &ctx.accounts.destination_account;
+        let token_mint_account = &ctx.accounts.token_mint_account;
+        let token_program = &ctx.accounts.token_program;
+
+        let decimals = Mint::try_from_account_info(token_mint_account)?.decimals;
+
+        // A multisig fee owner approved by its signers signs with its seeds
+        let multisig = check_owner_approval(owner, ctx.remaining_accounts)?;
+        let multisig_seeds = multisig.as_ref().map(|multisig| (multisig.multisig_id.to_le_bytes(), [multisig.bump]));
+        let seed: Vec<&[u8]> = match (&multisig, &multisig_seeds) {
+            (Some(multisig), Some((multisig_id, bump))) => vec![MULTISIG, multisig.creator.as_ref(), multisig_id, bump],
+            _ => vec![],
+        };
+        let seeds = [&seed[..]];
+        let signer_seeds: &[&[&[u8]]] = if multisig.is_some() { &seeds } else { &[] };
+
+        // Transfer fee to fee account
+        transfer_token_checked(
+            token_program,
+            owner,
+            fee_account,
+            token_mint_account,
+            destination_account,
+            amount,
+            decimals,
+            &[],
+            signer_seeds
+        )?;
+
+        msg!("DEBUG: seed {:?}", amount);
+
+        let accounting = nft_marketplace_account
+            .get_accounting_mut(token_mint_account.key)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+        accounting.fees_withdrawn = accounting.fees_withdrawn.saturating_add(amount);
+
+        // transfer_token(owner, from_pubkey, to_pubkey, amount, signer_seeds)
+
+        Ok(())
+    }
+
+    /// Pay `amount` of the fee account to the fee split recipients of the marketplace pro rata,
+    /// the last recipient receiving the rounding remainder. `remaining_accounts` holds a token
+    /// account of each recipient in table order, followed by the multisig signers if any.
+    pub fn distribute_fees<'info>(ctx: Context<'_, '_, '_, 'info, DistributeFeesContext<'info>>, amount: u64) -> Result<()> {
+        let nft_marketplace_account = &mut ctx.accounts.nft_marketplace_account;
+        let fee_account = &ctx.accounts.fee_account;
+        let owner = &ctx.accounts.owner;
+        let token_mint_account = &ctx.accounts.token_mint_account;
+        let token_program = &ctx.accounts.token_program;
+
+        let fee_splits = nft_marketplace_account.fee_splits.clone();
+        require!(ctx.remaining_accounts.len() >= fee_splits.len(), ErrorCode::FeeRecipientNotCorrect);
+        let (recipient_accounts, signers) = ctx.remaining_accounts.split_at(fee_splits.len());
+
+        let decimals = Mint::try_from_account_info(token_mint_account)?.decimals;
+
+        // A multisig fee owner approved by its signers signs with its seeds
+        let multisig = check_owner_approval(owner, signers)?;
+        let multisig_seeds = multisig.as_ref().map(|multisig| (multisig.multisig_id.to_le_bytes(), [multisig.bump]));
+        let seed: Vec<&[u8]> = match (&multisig, &multisig_seeds) {
+            (Some(multisig), Some((multisig_id, bump))) => vec![MULTISIG, multisig.creator.as_ref(), multisig_id, bump],
+            _ => vec![],
+        };
+        let seeds = [&seed[..]];
+        let signer_seeds: &[&[&[u8]]] = if multisig.is_some() { &seeds } else { &[] };
+
+        let mut distributed: u64 = 0;
+        for (index, (fee_split, recipient_account)) in fee_splits.iter().zip(recipient_accounts).enumerate() {
+            require!(*recipient_account.owner == *token_mint_account.owner, ErrorCode::FeeRecipientNotCorrect);
+            let recipient_token_account = TokenAccount::try_from_account_info(recipient_account)?;
+            require!(
+                recipient_token_account.owner == fee_split.recipient && recipient_token_account.mint == *token_mint_account.key,
+                ErrorCode::FeeRecipientNotCorrect
+            );
+
+            let share = if index == fee_splits.len() - 1 {
+                amount - distributed
+            } else {
+                (amount as u128 * fee_split.basis_points as u128 / 10000) as u64
+            };
+            distributed += share;
+
+            if share == 0 {
+                continue;
+            }
+
+            transfer_token_checked(
+                token_program,
+                owner,
+                fee_account,
+                token_mint_account,
+                recipient_account,
+                share,
+                decimals,
+                &[],
+                signer_seeds
+            )?;
+
+        }
+
+        let accounting = nft_marketplace_account
+            .get_accounting_mut(token_mint_account.key)
+            .ok_or(ErrorCode::CurrencyNotAccepted)?;
+        accounting.fees_withdrawn = accounting.fees_withdrawn.saturating_add(amount);
+
+        Ok(())
+    }
+
+    /// Create an M-of-N multisig which can own marketplaces and fee accounts
+    pub fn create_multisig(
+        ctx: Context<CreateMultisigContext>,
+        multisig_id: u64,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(Multisig::is_valid_signer_set(&signers, threshold), ErrorCode::MultisigNotCorrect);
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.creator = ctx.accounts.creator.key();
+        multisig.multisig_id = multisig_id;
+        multisig.bump = *ctx.bumps.get("multisig").unwrap();
+        multisig.threshold = threshold;
+        multisig.signers = signers;
+
+
+        Ok(())
+    }
+
+    /// Replace the signers and threshold of a multisig, signed by the multisig through a proposal
+    pub fn set_multisig_signers(ctx: Context<SetMultisigSignersContext>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        require!(Multisig::is_valid_signer_set(&signers, threshold), ErrorCode::MultisigNotCorrect);
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.threshold = threshold;
+        multisig.signers = signers;
+
+
+        Ok(())
+    }
+
+    /// Propose an instruction for the multisig to sign once enough of its signers approve it,
+    /// the proposer approving it first. Only the multisig can be a signer of the instruction.
+    pub fn create_proposal(
+        ctx: Context<CreateProposalContext>,
+        instruction_program_id: Pubkey,
+        instruction_accounts: Vec<ProposalAccount>,
+        instruction_data: Vec<u8>,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let multisig = &mut ctx.accounts.multisig;
+        let proposer = &ctx.accounts.proposer;
+
+        require!(instruction_accounts.len() <= MAX_PROPOSAL_ACCOUNTS, ErrorCode::ProposalNotCorrect);
+        require!(instruction_data.len() <= MAX_PROPOSAL_DATA_LEN, ErrorCode::ProposalNotCorrect);
+        require!(
+            instruction_accounts.iter().all(|account| !account.is_signer || account.pubkey == multisig.key()),
+            ErrorCode::ProposalNotCorrect
+        );
+
+        proposal.multisig = multisig.key();
+        proposal.proposer = proposer.key();
+        proposal.proposal_id = multisig.proposal_count;
+        proposal.bump = *ctx.bumps.get("proposal").unwrap();
+        proposal.program_id = instruction_program_id;
+        proposal.accounts = instruction_accounts;
+        proposal.data = instruction_data;
+        proposal.approvals = vec![proposer.key()];
+        multisig.proposal_count += 1;
+
+
+        Ok(())
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposalContext>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let multisig = &ctx.accounts.multisig;
+        let signer = ctx.accounts.signer.key();
+
+        if !proposal.approvals.contains(&signer) {
+            // Approvals of signers removed since do not count anymore
+            proposal.approvals.retain(|approval| multisig.is_signer(approval));
+            proposal.approvals.push(signer);
+        }
+
+
+        Ok(())
+    }
+
+    /// Execute a proposal approved by enough signers, signed by the multisig.
+    /// `remaining_accounts` holds the accounts of the proposed instruction and its program.
+    pub fn execute_proposal<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteProposalContext<'info>>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let multisig = &ctx.accounts.multisig;
+
+        require!(
+            multisig.count_approvals(proposal.approvals.iter()) >= multisig.threshold as usize,
+            ErrorCode::OwnerApprovalNotMet
+        );
+
+        // Saved before the call so the instruction cannot execute the proposal again
+        proposal.executed = true;
+        proposal.exit(ctx.program_id)?;
+
+        let instruction = Instruction {
+            program_id: proposal.program_id,
+            accounts: proposal.accounts
+                .iter()
+                .map(|account| AccountMeta {
+                    pubkey: account.pubkey,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                })
+                .collect(),
+            data: proposal.data.clone(),
+        };
+
+        let seed : &[&[u8]] = &[
+            MULTISIG,
+            multisig.creator.as_ref(),
+            &multisig.multisig_id.to_le_bytes(),
+            &[multisig.bump]
+        ];
+
+        invoke_signed(&instruction, ctx.remaining_accounts, &[seed])?;
 
-        // transfer_token(owner, from_pubkey, to_pubkey, amount, signer_seeds)
 
         Ok(())
     }
@@ -1,5 +1,63 @@
 use anchor_lang::prelude::*;
 
+use crate::constant::{
+    MAX_ACCEPTED_CURRENCIES,
+    MAX_BUNDLE_ITEMS,
+    MAX_FEE_SPLITS,
+    MAX_FEE_TIERS,
+    MAX_MULTISIG_SIGNERS,
+    MAX_PRICE_OBSERVATIONS,
+    MAX_PROPOSAL_ACCOUNTS,
+    MAX_PROPOSAL_DATA_LEN,
+    MAX_SWAP_ASSETS,
+};
+
+/// Largest serialized size of a type, in bytes, used to size accounts from their fields
+pub trait Space {
+    const SPACE: usize;
+}
+
+impl Space for u8 {
+    const SPACE: usize = 1;
+}
+
+impl Space for bool {
+    const SPACE: usize = 1;
+}
+
+impl Space for u16 {
+    const SPACE: usize = 2;
+}
+
+impl Space for u64 {
+    const SPACE: usize = 8;
+}
+
+impl Space for i64 {
+    const SPACE: usize = 8;
+}
+
+impl Space for u128 {
+    const SPACE: usize = 16;
+}
+
+impl Space for Pubkey {
+    const SPACE: usize = 32;
+}
+
+impl<T: Space> Space for Option<T> {
+    const SPACE: usize = 1 + T::SPACE;
+}
+
+impl<T: Space, const N: usize> Space for [T; N] {
+    const SPACE: usize = N * T::SPACE;
+}
+
+/// Size of a `Vec` of up to `max_len` items
+pub const fn vec_space<T: Space>(max_len: usize) -> usize {
+    4 + max_len * T::SPACE
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct Creator {
     pub address: Pubkey,
@@ -48,21 +106,316 @@ pub struct DataV2 {
 }
 
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct AcceptedCurrency {
+    pub mint: Pubkey,                  // The mint address of the currency
+    pub fee_account: Pubkey,           // Account to receive fees in this currency
+    pub seller_fee_basis_points: u16,  // Percent fee to take from seller
+    pub buyer_fee_basis_points: u16,   // Percent fee charged to buyer on top of the price
+    pub min_list_price: u64,           // Lowest price listings can ask, 0 for no minimum
+    pub max_list_price: u64,           // Highest price listings can ask, 0 for no maximum
+    pub price_feed: Option<Pubkey>,    // Price feed trusted to settle USD-pegged listings, None if not allowed
+}
+
+impl Space for AcceptedCurrency {
+    const SPACE: usize = Self::LEN;
+}
+
+impl AcceptedCurrency {
+    pub const LEN: usize = Pubkey::SPACE + Pubkey::SPACE + u16::SPACE + u16::SPACE + u64::SPACE + u64::SPACE
+        + Option::<Pubkey>::SPACE;
+
+    /// Checks if `price` is within the listing price bounds of the currency
+    pub fn is_list_price_allowed(&self, price: u64) -> bool {
+        price >= self.min_list_price && (self.max_list_price == 0 || price <= self.max_list_price)
+    }
+
+    /// Break down a sale at `price` into the maker fee taken from the seller proceeds
+    /// and the taker fee charged to the buyer on top of the price
+    pub fn get_fee_quote(&self, price: u64) -> Option<FeeQuote> {
+        let maker_fee = u64::try_from(price as u128 * self.seller_fee_basis_points as u128 / 10000).ok()?;
+        let taker_fee = u64::try_from(price as u128 * self.buyer_fee_basis_points as u128 / 10000).ok()?;
+        Some(FeeQuote {
+            price,
+            maker_fee,
+            taker_fee,
+            seller_receives: price.checked_sub(maker_fee)?,
+            buyer_pays: price.checked_add(taker_fee)?,
+        })
+    }
+}
+
+/// Cost breakdown of a sale, before any Token-2022 transfer fee
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct FeeQuote {
+    pub price: u64,           // The price of the listing
+    pub maker_fee: u64,       // Fee taken from the seller proceeds
+    pub taker_fee: u64,       // Fee charged to the buyer on top of the price
+    pub seller_receives: u64, // The price minus the maker fee
+    pub buyer_pays: u64,      // The price plus the taker fee
+}
+
+/// Requirement a buyer must meet to buy a gated listing
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum TokenGate {
+    Token { mint: Pubkey, min_amount: u64 }, // Hold at least `min_amount` of `mint`
+    Collection { collection: Pubkey },       // Hold an NFT of the verified `collection`
+}
+
+impl Space for TokenGate {
+    const SPACE: usize = Self::LEN;
+}
+
+impl TokenGate {
+    pub const LEN: usize = 1 + Pubkey::SPACE + u64::SPACE;
+}
+
+/// Asset whose holdings qualify sellers and buyers for a fee tier
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum FeeTierAsset {
+    Token { mint: Pubkey },             // Holdings are the balance of `mint`
+    Collection { collection: Pubkey },  // Holding an NFT of the verified `collection` counts as 1
+}
+
+impl Space for FeeTierAsset {
+    const SPACE: usize = Self::LEN;
+}
+
+impl FeeTierAsset {
+    pub const LEN: usize = 1 + Pubkey::SPACE;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct FeeTier {
+    pub min_amount: u64,              // Minimum holdings to qualify for the tier
+    pub seller_fee_basis_points: u16, // Maker fee of the tier
+    pub buyer_fee_basis_points: u16,  // Taker fee of the tier
+}
+
+impl Space for FeeTier {
+    const SPACE: usize = Self::LEN;
+}
+
+impl FeeTier {
+    pub const LEN: usize = u64::SPACE + u16::SPACE + u16::SPACE;
+}
+
+/// Share of the creator royalty buyers pay on sales
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub enum RoyaltyPolicy {
+    #[default]
+    Enforced,                      // Buyers pay the full royalty
+    Minimum { basis_points: u16 }, // Buyers pay at least `basis_points` of the royalty
+    Optional,                      // Buyers choose the share of the royalty they pay
+}
+
+
+impl Space for RoyaltyPolicy {
+    const SPACE: usize = Self::LEN;
+}
+
+impl RoyaltyPolicy {
+    pub const LEN: usize = 1 + u16::SPACE;
+
+    /// Share of the royalty, in basis points, paid by a buyer choosing `chosen_basis_points`,
+    /// the full royalty if the buyer does not choose
+    pub fn get_royalty_share(&self, chosen_basis_points: Option<u16>) -> u16 {
+        let chosen_basis_points = chosen_basis_points.unwrap_or(10000).min(10000);
+        match self {
+            RoyaltyPolicy::Enforced => 10000,
+            RoyaltyPolicy::Minimum { basis_points } => chosen_basis_points.max((*basis_points).min(10000)),
+            RoyaltyPolicy::Optional => chosen_basis_points,
+        }
+    }
+
+    /// Part of `total_royalty`, paid at `royalty_share`, taken out of the seller proceeds.
+    /// Sellers pay the share the policy enforces, buyers any share above it on top of the price.
+    pub fn get_seller_royalty(&self, total_royalty: u64, royalty_share: u16) -> u64 {
+        if royalty_share == 0 {
+            return 0;
+        }
+        let seller_share = self.get_royalty_share(Some(0)).min(royalty_share);
+        (total_royalty as u128 * seller_share as u128 / royalty_share as u128) as u64
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct CurrencyAccounting {
+    pub mint: Pubkey,         // The mint address of the currency
+    pub total_volume: u128,   // Sum of the sale prices
+    pub sale_count: u64,      // Number of NFTs sold
+    pub fees_accrued: u64,    // Fees received by the fee account, net of transfer fees
+    pub fees_withdrawn: u64,  // Fees taken out of the fee account with `withdraw_fee` or `distribute_fees`
+}
+
+impl Space for CurrencyAccounting {
+    const SPACE: usize = Self::LEN;
+}
+
+impl CurrencyAccounting {
+    pub const LEN: usize = Pubkey::SPACE + u128::SPACE + u64::SPACE + u64::SPACE + u64::SPACE;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct FeeSplit {
+    pub recipient: Pubkey,   // The wallet receiving the share
+    pub basis_points: u16,   // Share of the distributed fees
+}
+
+impl Space for FeeSplit {
+    const SPACE: usize = Self::LEN;
+}
+
+impl FeeSplit {
+    pub const LEN: usize = Pubkey::SPACE + u16::SPACE;
+
+    /// Checks if `fee_splits` is empty, or up to `MAX_FEE_SPLITS` shares of distinct recipients summing to 10000
+    pub fn is_valid_table(fee_splits: &[FeeSplit]) -> bool {
+        fee_splits.is_empty()
+            || (fee_splits.len() <= MAX_FEE_SPLITS
+                && fee_splits.iter().all(|split| split.basis_points > 0)
+                && fee_splits.iter().map(|split| split.basis_points as u32).sum::<u32>() == 10000
+                && fee_splits
+                    .iter()
+                    .enumerate()
+                    .all(|(index, split)| fee_splits[..index].iter().all(|other| other.recipient != split.recipient)))
+    }
+}
+
 // Marketplace state
 #[account]
 #[derive(Default)]
 pub struct MarketplaceNFT {
+    pub version: u8,                // Layout version, upgraded by `migrate_marketplace`
     pub spl_token_mint: Pubkey,     // The mint address of the token to be used as the Marketplace currency
     pub fee_account: Pubkey,        // Account to receive fees
     pub seller_fee_basis_points: u16,  // Percent fee to take from seller
     pub owner: Pubkey,            // The public key of the Marketplace instance creator
     pub bump: u8,                   // Bump seed for the Marketplace instance
-    pub fee_bump: u8,               // Bump seed for the fee account
+    pub signer_bump: u8,            // Bump seed for the program signer of the marketplace
+    pub accepted_currencies: Vec<AcceptedCurrency>, // Currencies listings can be priced in, including `spl_token_mint`
+    pub token_gate: Option<TokenGate>, // Requirement for buyers of every listing without its own gate
+    pub referral_basis_points: u16, // Share of the fee paid to the referrer of a sale
+    pub buyer_fee_basis_points: u16, // Percent fee charged to buyer on top of the price
+    pub fee_tier_asset: Option<FeeTierAsset>, // Asset holders present to get a fee tier
+    pub fee_tiers: Vec<FeeTier>, // Discounted fees by minimum holdings, ascending
+    pub royalty_policy: RoyaltyPolicy, // Share of the creator royalty buyers pay
+    pub accounting: Vec<CurrencyAccounting>, // Volume and fee bookkeeping by currency
+    pub change_delay: i64,          // Seconds fee changes are queued for before they apply
+    pub fee_splits: Vec<FeeSplit>,  // Recipients `distribute_fees` pays, empty if fees are withdrawn whole
+    pub reserved: [u8; 24],         // Room for new fields without growing the account
+}
+
+impl MarketplaceNFT {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8
+        + u8::SPACE // version
+        + Pubkey::SPACE // spl_token_mint
+        + Pubkey::SPACE // fee_account
+        + u16::SPACE // seller_fee_basis_points
+        + Pubkey::SPACE // owner
+        + u8::SPACE // bump
+        + u8::SPACE // signer_bump
+        + vec_space::<AcceptedCurrency>(MAX_ACCEPTED_CURRENCIES)
+        + Option::<TokenGate>::SPACE
+        + u16::SPACE // referral_basis_points
+        + u16::SPACE // buyer_fee_basis_points
+        + Option::<FeeTierAsset>::SPACE
+        + vec_space::<FeeTier>(MAX_FEE_TIERS)
+        + RoyaltyPolicy::SPACE
+        + vec_space::<CurrencyAccounting>(MAX_ACCEPTED_CURRENCIES)
+        + i64::SPACE // change_delay
+        + vec_space::<FeeSplit>(MAX_FEE_SPLITS)
+        + <[u8; 24]>::SPACE;
+
+    /// Size of the unversioned marketplaces, from `spl_token_mint` to `fee_bump` and 7 bytes of padding
+    pub const BASELINE_LEN: usize = 115;
+
+    /// Read an unversioned marketplace, the fields it predates taking their defaults.
+    /// It only accepted its primary currency.
+    pub fn deserialize_baseline(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let mut marketplace = MarketplaceNFT {
+            version: Self::VERSION,
+            spl_token_mint: AnchorDeserialize::deserialize(buf)?,
+            fee_account: AnchorDeserialize::deserialize(buf)?,
+            seller_fee_basis_points: AnchorDeserialize::deserialize(buf)?,
+            owner: AnchorDeserialize::deserialize(buf)?,
+            bump: AnchorDeserialize::deserialize(buf)?,
+            signer_bump: AnchorDeserialize::deserialize(buf)?,
+            ..Default::default()
+        };
+        marketplace.accepted_currencies = vec![AcceptedCurrency {
+            mint: marketplace.spl_token_mint,
+            fee_account: marketplace.fee_account,
+            seller_fee_basis_points: marketplace.seller_fee_basis_points,
+            ..Default::default()
+        }];
+        Ok(marketplace)
+    }
+
+    /// Bookkeeping of `mint`, started for accepted currencies, in the place of a removed currency once full
+    pub fn get_accounting_mut(&mut self, mint: &Pubkey) -> Option<&mut CurrencyAccounting> {
+        let index = match self.accounting.iter().position(|accounting| accounting.mint == *mint) {
+            Some(index) => index,
+            None => {
+                self.get_accepted_currency(mint)?;
+                let accounting = CurrencyAccounting { mint: *mint, ..Default::default() };
+                if self.accounting.len() < MAX_ACCEPTED_CURRENCIES {
+                    self.accounting.push(accounting);
+                    self.accounting.len() - 1
+                } else {
+                    let index = self.accounting
+                        .iter()
+                        .position(|accounting| self.get_accepted_currency(&accounting.mint).is_none())?;
+                    self.accounting[index] = accounting;
+                    index
+                }
+            }
+        };
+        self.accounting.get_mut(index)
+    }
+
+    /// Book `count` sales of `mint` worth `volume`, whose fees left `fee` in the fee account
+    pub fn record_sales(&mut self, mint: &Pubkey, count: u64, volume: u64, fee: u64) -> Option<()> {
+        let accounting = self.get_accounting_mut(mint)?;
+        accounting.total_volume += volume as u128;
+        accounting.sale_count += count;
+        accounting.fees_accrued = accounting.fees_accrued.checked_add(fee)?;
+        Some(())
+    }
+
+    /// Share of `fee` paid to the referrer of a sale
+    pub fn get_referral_fee(&self, fee: u64) -> Option<u64> {
+        let referral_fee = (fee as u128).checked_mul(self.referral_basis_points as u128)? / 10000;
+        u64::try_from(referral_fee).ok()
+    }
+
+    pub fn get_accepted_currency(&self, mint: &Pubkey) -> Option<&AcceptedCurrency> {
+        self.accepted_currencies.iter().find(|currency| currency.mint == *mint)
+    }
+
+    /// Fees of the tier at `index`, None once fee tiers are turned off or the tier is removed
+    pub fn get_fee_tier_at(&self, index: Option<u8>) -> Option<&FeeTier> {
+        self.fee_tier_asset.as_ref()?;
+        self.fee_tiers.get(index? as usize)
+    }
+
+    /// Index and fees of the highest tier `holdings` qualify for
+    pub fn get_fee_tier(&self, holdings: u64) -> Option<(u8, &FeeTier)> {
+        self.fee_tiers
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, tier)| tier.min_amount <= holdings)
+            .map(|(index, tier)| (index as u8, tier))
+    }
 }
 
 #[account]
 #[derive(Default)]
 pub struct SellerTradeState {
+    pub version: u8,    // Layout version, upgraded by `migrate_listing`
     pub seller: Pubkey, // The public key of the seller
     pub bump: u8,       // Bump seed for the seller
     pub list_price: u64, // The price the seller is asking for
@@ -70,4 +423,631 @@ pub struct SellerTradeState {
     pub nft_marketplace_account: Pubkey, // The NFT marketplace account
     pub nft_token_account: Pubkey, // The NFT token account
     pub token_mint_account: Pubkey, // The token mint account (token currency)
+    pub is_programmable: bool, // The NFT is a pNFT moved through Token Metadata
+    pub usd_price: Option<u64>, // The USD price (6 decimals) for listings pegged to USD
+    pub price_feed: Option<Pubkey>, // The price feed used to settle a USD-pegged listing
+    pub reserved_buyer: Option<Pubkey>, // The only buyer allowed to buy a private listing
+    pub token_gate: Option<TokenGate>, // Requirement for buyers, overriding the marketplace one
+    pub collection: Option<Pubkey>, // The verified collection whose stats count the listing
+    pub seller_fee_tier: Option<u8>, // Index of the fee tier the seller held the asset of when listing
+    pub claimable_payout: bool, // The proceeds are credited to claimable balances instead of paid out
+    pub reserved: [u8; 29], // Room for new fields without growing the account
+}
+
+impl SellerTradeState {
+    pub const VERSION: u8 = 1;
+
+    pub const LEN: usize = 8
+        + u8::SPACE // version
+        + Pubkey::SPACE // seller
+        + u8::SPACE // bump
+        + u64::SPACE // list_price
+        + Pubkey::SPACE // mint_nft_account
+        + Pubkey::SPACE // nft_marketplace_account
+        + Pubkey::SPACE // nft_token_account
+        + Pubkey::SPACE // token_mint_account
+        + bool::SPACE // is_programmable
+        + Option::<u64>::SPACE // usd_price
+        + Option::<Pubkey>::SPACE // price_feed
+        + Option::<Pubkey>::SPACE // reserved_buyer
+        + Option::<TokenGate>::SPACE
+        + Option::<Pubkey>::SPACE // collection
+        + Option::<u8>::SPACE // seller_fee_tier
+        + bool::SPACE // claimable_payout
+        + <[u8; 29]>::SPACE;
+
+    /// Size of the unversioned listings, from `seller` to `token_mint_account`
+    pub const BASELINE_LEN: usize = 177;
+
+    /// Read an unversioned listing, the fields it predates taking their defaults
+    pub fn deserialize_baseline(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(SellerTradeState {
+            version: Self::VERSION,
+            seller: AnchorDeserialize::deserialize(buf)?,
+            bump: AnchorDeserialize::deserialize(buf)?,
+            list_price: AnchorDeserialize::deserialize(buf)?,
+            mint_nft_account: AnchorDeserialize::deserialize(buf)?,
+            nft_marketplace_account: AnchorDeserialize::deserialize(buf)?,
+            nft_token_account: AnchorDeserialize::deserialize(buf)?,
+            token_mint_account: AnchorDeserialize::deserialize(buf)?,
+            ..Default::default()
+        })
+    }
+}
+
+/// Trading stats of a verified collection for sales in one currency of a marketplace
+#[account]
+#[derive(Default)]
+pub struct CollectionStats {
+    pub nft_marketplace_account: Pubkey, // The NFT marketplace account
+    pub collection: Pubkey,        // The verified collection
+    pub token_mint_account: Pubkey, // The token mint account (token currency)
+    pub bump: u8,                  // Bump seed for the collection stats
+    pub total_volume: u128,        // Sum of the sale prices
+    pub sale_count: u64,           // Number of sales
+    pub last_sale_price: u64,      // Price of the latest sale
+    pub last_sale_time: i64,       // Unix timestamp of the latest sale
+    pub all_time_high: u64,        // Highest sale price
+    pub active_listings: u64,      // Number of open listings counted in the stats
+}
+
+impl CollectionStats {
+    pub const LEN: usize = 8
+        + Pubkey::SPACE // nft_marketplace_account
+        + Pubkey::SPACE // collection
+        + Pubkey::SPACE // token_mint_account
+        + u8::SPACE // bump
+        + u128::SPACE // total_volume
+        + u64::SPACE // sale_count
+        + u64::SPACE // last_sale_price
+        + i64::SPACE // last_sale_time
+        + u64::SPACE // all_time_high
+        + u64::SPACE; // active_listings
+
+    pub fn record_sale(&mut self, price: u64, timestamp: i64) {
+        self.total_volume += price as u128;
+        self.sale_count += 1;
+        self.last_sale_price = price;
+        self.last_sale_time = timestamp;
+        self.all_time_high = self.all_time_high.max(price);
+    }
+}
+
+/// Sale proceeds and royalties credited to a wallet, withdrawn from the vault with `claim`
+#[account]
+#[derive(Default)]
+pub struct ClaimableBalance {
+    pub wallet: Pubkey, // The public key allowed to claim the balance
+    pub bump: u8,       // Bump seed for the claimable balance
+    pub nft_marketplace_account: Pubkey, // The NFT marketplace account the sales were made on
+    pub token_mint_account: Pubkey, // The token mint account (token currency)
+    pub amount: u64,    // The amount left to claim
+}
+
+impl ClaimableBalance {
+    pub const LEN: usize = 8
+        + Pubkey::SPACE // wallet
+        + u8::SPACE // bump
+        + Pubkey::SPACE // nft_marketplace_account
+        + Pubkey::SPACE // token_mint_account
+        + u64::SPACE; // amount
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct PriceObservation {
+    pub timestamp: i64,         // Unix timestamp of the sale
+    pub cumulative_price: u128, // Sum of the last sale price over every second until `timestamp`
+}
+
+impl Space for PriceObservation {
+    const SPACE: usize = Self::LEN;
+}
+
+impl PriceObservation {
+    pub const LEN: usize = i64::SPACE + u128::SPACE;
+}
+
+/// Ring buffer of the sale prices of a collection, read as a time-weighted average price
+#[account]
+#[derive(Default)]
+pub struct PriceObservations {
+    pub collection_stats: Pubkey, // The collection stats whose sales are observed
+    pub bump: u8,                 // Bump seed for the price observations
+    pub index: u16,               // Index of the latest observation
+    pub last_price: u64,          // Price of the latest sale
+    pub observations: Vec<PriceObservation>, // Observations, oldest overwritten once full
+}
+
+impl PriceObservations {
+    pub const LEN: usize = 8
+        + Pubkey::SPACE // collection_stats
+        + u8::SPACE // bump
+        + u16::SPACE // index
+        + u64::SPACE // last_price
+        + vec_space::<PriceObservation>(MAX_PRICE_OBSERVATIONS);
+
+    pub fn record_sale(&mut self, price: u64, timestamp: i64) {
+        match self.observations.get(self.index as usize).cloned() {
+            None => self.observations.push(PriceObservation { timestamp, cumulative_price: 0 }),
+            // Sales within the same second only move the last price
+            Some(latest) if timestamp <= latest.timestamp => {}
+            Some(latest) => {
+                let observation = PriceObservation {
+                    timestamp,
+                    cumulative_price: latest.cumulative_price
+                        + self.last_price as u128 * (timestamp - latest.timestamp) as u128,
+                };
+                if self.observations.len() < MAX_PRICE_OBSERVATIONS {
+                    self.index = self.observations.len() as u16;
+                    self.observations.push(observation);
+                } else {
+                    self.index = ((self.index as usize + 1) % MAX_PRICE_OBSERVATIONS) as u16;
+                    self.observations[self.index as usize] = observation;
+                }
+            }
+        }
+        self.last_price = price;
+    }
+
+    /// Time-weighted average of the last sale price over the `window` seconds before `now`,
+    /// None if the observations do not go back far enough
+    pub fn get_twap(&self, window: i64, now: i64) -> Option<u64> {
+        let latest = self.observations.get(self.index as usize)?;
+        if window <= 0 || now < latest.timestamp {
+            return None;
+        }
+        let cumulative_price_at = |timestamp: i64, observation: &PriceObservation, price: u64| {
+            observation.cumulative_price + price as u128 * (timestamp - observation.timestamp) as u128
+        };
+        let start = now.checked_sub(window)?;
+        let end_cumulative_price = cumulative_price_at(now, latest, self.last_price);
+
+        // Walk back from the latest observation to the last one at or before `start`,
+        // the price after it being the one in effect until the next observation
+        let len = self.observations.len();
+        let mut next: Option<&PriceObservation> = None;
+        for offset in 0..len {
+            let observation = &self.observations[(self.index as usize + len - offset) % len];
+            if observation.timestamp <= start {
+                let price = match next {
+                    Some(next) => {
+                        let elapsed = (next.timestamp - observation.timestamp) as u128;
+                        u64::try_from((next.cumulative_price - observation.cumulative_price) / elapsed).ok()?
+                    }
+                    None => self.last_price,
+                };
+                let start_cumulative_price = cumulative_price_at(start, observation, price);
+                return u64::try_from((end_cumulative_price - start_cumulative_price) / window as u128).ok();
+            }
+            next = Some(observation);
+        }
+        None
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct BundleItem {
+    pub mint_nft_account: Pubkey,  // The mint account of the NFT
+    pub nft_token_account: Pubkey, // The NFT token account
+    pub collection: Option<Pubkey>, // The verified collection whose stats count the NFT
+}
+
+impl Space for BundleItem {
+    const SPACE: usize = Self::LEN;
+}
+
+impl BundleItem {
+    pub const LEN: usize = Pubkey::SPACE + Pubkey::SPACE + Option::<Pubkey>::SPACE;
+}
+
+#[account]
+#[derive(Default)]
+pub struct BundleListing {
+    pub seller: Pubkey, // The public key of the seller
+    pub bump: u8,       // Bump seed for the bundle listing
+    pub bundle_id: u64, // Id chosen by the seller to tell bundles apart
+    pub price: u64,     // The price the seller is asking for the whole bundle
+    pub nft_marketplace_account: Pubkey, // The NFT marketplace account
+    pub token_mint_account: Pubkey, // The token mint account (token currency)
+    pub items: Vec<BundleItem>, // The NFTs sold together
+}
+
+impl BundleListing {
+    pub const LEN: usize = 8
+        + Pubkey::SPACE // seller
+        + u8::SPACE // bump
+        + u64::SPACE // bundle_id
+        + u64::SPACE // price
+        + Pubkey::SPACE // nft_marketplace_account
+        + Pubkey::SPACE // token_mint_account
+        + vec_space::<BundleItem>(MAX_BUNDLE_ITEMS);
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct SwapAsset {
+    pub mint: Pubkey, // The mint of the NFT or currency token
+    pub amount: u64,  // The amount of tokens, 1 for NFTs
+}
+
+impl Space for SwapAsset {
+    const SPACE: usize = Self::LEN;
+}
+
+impl SwapAsset {
+    pub const LEN: usize = Pubkey::SPACE + u64::SPACE;
+}
+
+#[account]
+#[derive(Default)]
+pub struct Swap {
+    pub maker: Pubkey,  // The public key of the party depositing the offered assets
+    pub taker: Pubkey,  // The public key of the counterparty allowed to accept the swap
+    pub bump: u8,       // Bump seed for the swap
+    pub swap_id: u64,   // Id chosen by the maker to tell swaps apart
+    pub offered: Vec<SwapAsset>,   // The assets escrowed by the maker
+    pub requested: Vec<SwapAsset>, // The assets the maker expects back from the taker
+}
+
+impl Swap {
+    pub const LEN: usize = 8
+        + Pubkey::SPACE // maker
+        + Pubkey::SPACE // taker
+        + u8::SPACE // bump
+        + u64::SPACE // swap_id
+        + vec_space::<SwapAsset>(MAX_SWAP_ASSETS)
+        + vec_space::<SwapAsset>(MAX_SWAP_ASSETS);
+}
+
+/// M-of-N signer set which can own marketplaces and fee accounts
+#[account]
+#[derive(Default)]
+pub struct Multisig {
+    pub creator: Pubkey,       // The public key which created the multisig
+    pub multisig_id: u64,      // Id chosen by the creator to tell multisigs apart
+    pub bump: u8,              // Bump seed for the multisig
+    pub threshold: u8,         // Number of signers required to approve
+    pub signers: Vec<Pubkey>,  // The public keys allowed to approve
+    pub proposal_count: u64,   // Number of proposals created, the id of the next one
+}
+
+impl Multisig {
+    pub const LEN: usize = 8
+        + Pubkey::SPACE // creator
+        + u64::SPACE // multisig_id
+        + u8::SPACE // bump
+        + u8::SPACE // threshold
+        + vec_space::<Pubkey>(MAX_MULTISIG_SIGNERS)
+        + u64::SPACE; // proposal_count
+
+    /// Checks if `signers` is a set of 1 to `MAX_MULTISIG_SIGNERS` distinct keys `threshold` of them can approve
+    pub fn is_valid_signer_set(signers: &[Pubkey], threshold: u8) -> bool {
+        !signers.is_empty()
+            && signers.len() <= MAX_MULTISIG_SIGNERS
+            && threshold > 0
+            && threshold as usize <= signers.len()
+            && signers.iter().enumerate().all(|(index, signer)| !signers[..index].contains(signer))
+    }
+
+    pub fn is_signer(&self, key: &Pubkey) -> bool {
+        self.signers.contains(key)
+    }
+
+    /// Number of distinct signers of the multisig in `keys`
+    pub fn count_approvals<'a>(&self, keys: impl Iterator<Item = &'a Pubkey>) -> usize {
+        let mut approved = vec![false; self.signers.len()];
+        for key in keys {
+            if let Some(index) = self.signers.iter().position(|signer| signer == key) {
+                approved[index] = true;
+            }
+        }
+        approved.iter().filter(|approved| **approved).count()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct ProposalAccount {
+    pub pubkey: Pubkey,    // The account passed to the instruction
+    pub is_signer: bool,   // The account signs, only the multisig can
+    pub is_writable: bool, // The account is writable
+}
+
+impl Space for ProposalAccount {
+    const SPACE: usize = Self::LEN;
+}
+
+impl ProposalAccount {
+    pub const LEN: usize = Pubkey::SPACE + bool::SPACE + bool::SPACE;
+}
+
+/// Instruction the signers of a multisig approve asynchronously, executed signed by the multisig
+#[account]
+#[derive(Default)]
+pub struct Proposal {
+    pub multisig: Pubkey,   // The multisig the proposal is for
+    pub proposer: Pubkey,   // The signer which created the proposal
+    pub proposal_id: u64,   // Index of the proposal in the multisig
+    pub bump: u8,           // Bump seed for the proposal
+    pub program_id: Pubkey, // The program of the instruction
+    pub accounts: Vec<ProposalAccount>, // The accounts of the instruction
+    pub data: Vec<u8>,      // The data of the instruction
+    pub approvals: Vec<Pubkey>, // The signers which approved
+    pub executed: bool,     // The instruction was executed
+}
+
+impl Proposal {
+    pub const LEN: usize = 8
+        + Pubkey::SPACE // multisig
+        + Pubkey::SPACE // proposer
+        + u64::SPACE // proposal_id
+        + u8::SPACE // bump
+        + Pubkey::SPACE // program_id
+        + vec_space::<ProposalAccount>(MAX_PROPOSAL_ACCOUNTS)
+        + vec_space::<u8>(MAX_PROPOSAL_DATA_LEN)
+        + vec_space::<Pubkey>(MAX_MULTISIG_SIGNERS)
+        + bool::SPACE; // executed
+}
+
+/// Fee change of a currency queued until `effective_time`, so sellers see it coming
+#[account]
+#[derive(Default)]
+pub struct PendingChange {
+    pub marketplace: Pubkey,          // The marketplace the change is for
+    pub spl_token_mint: Pubkey,       // The currency the change is for
+    pub payer: Pubkey,                // The public key which paid for the account, refunded when closed
+    pub bump: u8,                     // Bump seed for the pending change
+    pub fee_account: Pubkey,          // New account to receive fees in the currency
+    pub seller_fee_basis_points: u16, // New percent fee to take from seller
+    pub buyer_fee_basis_points: u16,  // New percent fee charged to buyer on top of the price
+    pub change_delay: i64,            // New change delay of the marketplace
+    pub effective_time: i64,          // Time from which the change can be applied
+    pub fee_splits: Option<Vec<FeeSplit>>, // New fee splits of the marketplace, None to keep them
+}
+
+impl PendingChange {
+    pub const LEN: usize = 8
+        + Pubkey::SPACE // marketplace
+        + Pubkey::SPACE // spl_token_mint
+        + Pubkey::SPACE // payer
+        + u8::SPACE // bump
+        + Pubkey::SPACE // fee_account
+        + u16::SPACE // seller_fee_basis_points
+        + u16::SPACE // buyer_fee_basis_points
+        + i64::SPACE // change_delay
+        + i64::SPACE // effective_time
+        + 1 + vec_space::<FeeSplit>(MAX_FEE_SPLITS); // fee_splits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    #[test]
+    fn full_marketplace_fits_len() {
+        let marketplace = MarketplaceNFT {
+            accepted_currencies: vec![
+                AcceptedCurrency { price_feed: Some(Pubkey::default()), ..Default::default() };
+                MAX_ACCEPTED_CURRENCIES
+            ],
+            token_gate: Some(TokenGate::Token { mint: Pubkey::default(), min_amount: 0 }),
+            fee_tier_asset: Some(FeeTierAsset::Token { mint: Pubkey::default() }),
+            fee_tiers: vec![FeeTier::default(); MAX_FEE_TIERS],
+            royalty_policy: RoyaltyPolicy::Minimum { basis_points: 0 },
+            accounting: vec![CurrencyAccounting::default(); MAX_ACCEPTED_CURRENCIES],
+            fee_splits: vec![FeeSplit::default(); MAX_FEE_SPLITS],
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        marketplace.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), MarketplaceNFT::LEN);
+    }
+
+    #[test]
+    fn full_listing_fits_len() {
+        let trade_state = SellerTradeState {
+            usd_price: Some(0),
+            price_feed: Some(Pubkey::default()),
+            reserved_buyer: Some(Pubkey::default()),
+            token_gate: Some(TokenGate::Token { mint: Pubkey::default(), min_amount: 0 }),
+            collection: Some(Pubkey::default()),
+            seller_fee_tier: Some(0),
+            claimable_payout: true,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        trade_state.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), SellerTradeState::LEN);
+    }
+
+    #[test]
+    fn deserialize_baseline_marketplace() {
+        let spl_token_mint = Pubkey::new_unique();
+        let fee_account = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut data = MarketplaceNFT::DISCRIMINATOR.to_vec();
+        (spl_token_mint, fee_account, 250u16, owner, 254u8, 253u8).serialize(&mut data).unwrap();
+        data.resize(MarketplaceNFT::BASELINE_LEN, 0);
+
+        let marketplace = MarketplaceNFT::deserialize_baseline(&mut &data[8..]).unwrap();
+        assert_eq!(marketplace.version, MarketplaceNFT::VERSION);
+        assert_eq!(marketplace.spl_token_mint, spl_token_mint);
+        assert_eq!(marketplace.owner, owner);
+        assert_eq!(marketplace.signer_bump, 253);
+        assert_eq!(marketplace.accepted_currencies.len(), 1);
+        assert_eq!(marketplace.accepted_currencies[0].mint, spl_token_mint);
+        assert_eq!(marketplace.accepted_currencies[0].fee_account, fee_account);
+        assert_eq!(marketplace.accepted_currencies[0].seller_fee_basis_points, 250);
+    }
+
+    #[test]
+    fn deserialize_baseline_listing() {
+        let seller = Pubkey::new_unique();
+        let mut data = SellerTradeState::DISCRIMINATOR.to_vec();
+        (seller, 255u8, 1_000u64, [Pubkey::new_unique(); 4]).serialize(&mut data).unwrap();
+        assert_eq!(data.len(), SellerTradeState::BASELINE_LEN);
+
+        let trade_state = SellerTradeState::deserialize_baseline(&mut &data[8..]).unwrap();
+        assert_eq!(trade_state.version, SellerTradeState::VERSION);
+        assert_eq!(trade_state.seller, seller);
+        assert_eq!(trade_state.list_price, 1_000);
+        assert!(!trade_state.is_programmable);
+        assert_eq!(trade_state.collection, None);
+    }
+
+    #[test]
+    fn record_sale_within_the_same_second_only_moves_the_price() {
+        let mut price_observations = PriceObservations::default();
+        price_observations.record_sale(100, 10);
+        price_observations.record_sale(300, 10);
+        assert_eq!(price_observations.observations.len(), 1);
+        assert_eq!(price_observations.observations[0].cumulative_price, 0);
+        assert_eq!(price_observations.last_price, 300);
+    }
+
+    #[test]
+    fn get_twap_weights_prices_by_time() {
+        let mut price_observations = PriceObservations::default();
+        price_observations.record_sale(100, 0);
+        price_observations.record_sale(200, 10);
+        assert_eq!(price_observations.observations[1].cumulative_price, 1_000);
+
+        assert_eq!(price_observations.get_twap(20, 20), Some(150));
+        assert_eq!(price_observations.get_twap(10, 20), Some(200));
+        assert_eq!(price_observations.get_twap(15, 20), Some(166));
+        assert_eq!(price_observations.get_twap(21, 20), None);
+        assert_eq!(price_observations.get_twap(0, 20), None);
+        assert_eq!(price_observations.get_twap(10, 5), None);
+    }
+
+    #[test]
+    fn record_sale_overwrites_the_oldest_observation_once_full() {
+        let mut price_observations = PriceObservations::default();
+        let sales = MAX_PRICE_OBSERVATIONS as i64 + 5;
+        for timestamp in 0..sales {
+            price_observations.record_sale(timestamp as u64, timestamp);
+        }
+        let now = sales - 1;
+        assert_eq!(price_observations.observations.len(), MAX_PRICE_OBSERVATIONS);
+        assert_eq!(price_observations.index, 4);
+        assert_eq!(price_observations.observations[4].timestamp, now);
+
+        // Prices `now - 10` to `now - 1` were each in effect for a second
+        assert_eq!(price_observations.get_twap(10, now), Some((now as u64 - 10 + now as u64 - 1) / 2));
+        assert_eq!(price_observations.get_twap(MAX_PRICE_OBSERVATIONS as i64, now), None);
+    }
+
+    #[test]
+    fn record_sales_books_accepted_currencies() {
+        let mint = Pubkey::new_unique();
+        let mut marketplace = MarketplaceNFT {
+            accepted_currencies: vec![AcceptedCurrency { mint, ..Default::default() }],
+            ..Default::default()
+        };
+        assert_eq!(marketplace.record_sales(&mint, 2, 3_000, 60), Some(()));
+        assert_eq!(marketplace.record_sales(&mint, 1, 1_000, 20), Some(()));
+        assert_eq!(marketplace.accounting.len(), 1);
+        assert_eq!(marketplace.accounting[0].mint, mint);
+        assert_eq!(marketplace.accounting[0].sale_count, 3);
+        assert_eq!(marketplace.accounting[0].total_volume, 4_000);
+        assert_eq!(marketplace.accounting[0].fees_accrued, 80);
+
+        assert_eq!(marketplace.record_sales(&Pubkey::new_unique(), 1, 1_000, 20), None);
+        assert_eq!(marketplace.accounting.len(), 1);
+    }
+
+    #[test]
+    fn record_sales_reuses_the_accounting_of_removed_currencies_once_full() {
+        let booked_mints: Vec<Pubkey> = (0..MAX_ACCEPTED_CURRENCIES).map(|_| Pubkey::new_unique()).collect();
+        let mint = Pubkey::new_unique();
+        // Every booked currency but the second one is still accepted
+        let mut accepted_mints: Vec<Pubkey> = booked_mints.iter().filter(|booked| **booked != booked_mints[1]).cloned().collect();
+        accepted_mints.push(mint);
+        let mut marketplace = MarketplaceNFT {
+            accepted_currencies: accepted_mints.iter().map(|mint| AcceptedCurrency { mint: *mint, ..Default::default() }).collect(),
+            accounting: booked_mints.iter().map(|mint| CurrencyAccounting { mint: *mint, ..Default::default() }).collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(marketplace.record_sales(&mint, 1, 500, 10), Some(()));
+        assert_eq!(marketplace.accounting.len(), MAX_ACCEPTED_CURRENCIES);
+        assert_eq!(marketplace.accounting[1].mint, mint);
+        assert_eq!(marketplace.accounting[1].total_volume, 500);
+    }
+
+    #[test]
+    fn record_sales_rejects_fee_overflow() {
+        let mint = Pubkey::new_unique();
+        let mut marketplace = MarketplaceNFT {
+            accepted_currencies: vec![AcceptedCurrency { mint, ..Default::default() }],
+            ..Default::default()
+        };
+        assert_eq!(marketplace.record_sales(&mint, 1, 1_000, u64::MAX), Some(()));
+        assert_eq!(marketplace.record_sales(&mint, 1, 1_000, 1), None);
+    }
+
+    #[test]
+    fn get_referral_fee_does_not_overflow() {
+        let marketplace = MarketplaceNFT {
+            referral_basis_points: 2500,
+            ..Default::default()
+        };
+        assert_eq!(marketplace.get_referral_fee(1000), Some(250));
+        assert_eq!(marketplace.get_referral_fee(u64::MAX), Some(u64::MAX / 4));
+    }
+
+    #[test]
+    fn get_seller_royalty_leaves_the_chosen_share_to_the_buyer() {
+        assert_eq!(RoyaltyPolicy::Enforced.get_seller_royalty(500, 10000), 500);
+        assert_eq!(RoyaltyPolicy::Optional.get_seller_royalty(500, 10000), 0);
+        assert_eq!(RoyaltyPolicy::Optional.get_seller_royalty(0, 0), 0);
+        assert_eq!(RoyaltyPolicy::Minimum { basis_points: 2000 }.get_seller_royalty(500, 10000), 100);
+        assert_eq!(RoyaltyPolicy::Minimum { basis_points: 2000 }.get_seller_royalty(100, 2000), 100);
+    }
+
+    #[test]
+    fn get_fee_tier_at_ignores_removed_tiers() {
+        let mut marketplace = MarketplaceNFT {
+            fee_tier_asset: Some(FeeTierAsset::Token { mint: Pubkey::new_unique() }),
+            fee_tiers: vec![FeeTier { min_amount: 1, seller_fee_basis_points: 100, buyer_fee_basis_points: 0 }],
+            ..Default::default()
+        };
+        assert_eq!(marketplace.get_fee_tier_at(Some(0)).map(|tier| tier.seller_fee_basis_points), Some(100));
+        assert!(marketplace.get_fee_tier_at(Some(1)).is_none());
+        assert!(marketplace.get_fee_tier_at(None).is_none());
+
+        marketplace.fee_tier_asset = None;
+        assert!(marketplace.get_fee_tier_at(Some(0)).is_none());
+    }
+
+    #[test]
+    fn fee_split_tables() {
+        let split = |basis_points: u16| FeeSplit { recipient: Pubkey::new_unique(), basis_points };
+        assert!(FeeSplit::is_valid_table(&[]));
+        assert!(FeeSplit::is_valid_table(&[split(10000)]));
+        assert!(FeeSplit::is_valid_table(&[split(2500), split(2500), split(5000)]));
+
+        assert!(!FeeSplit::is_valid_table(&[split(5000)]));
+        assert!(!FeeSplit::is_valid_table(&[split(5000), split(5001)]));
+        assert!(!FeeSplit::is_valid_table(&[split(10000), split(0)]));
+        let too_many: Vec<FeeSplit> = (0..=MAX_FEE_SPLITS)
+            .map(|index| split(if index == 0 { 10000 - MAX_FEE_SPLITS as u16 * 1000 } else { 1000 }))
+            .collect();
+        assert!(!FeeSplit::is_valid_table(&too_many));
+
+        let duplicate = split(5000);
+        assert!(!FeeSplit::is_valid_table(&[duplicate.clone(), duplicate]));
+    }
+
+    #[test]
+    fn count_approvals_counts_distinct_signers() {
+        let signers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let multisig = Multisig { threshold: 2, signers: signers.clone(), ..Default::default() };
+        let outsider = Pubkey::new_unique();
+
+        assert_eq!(multisig.count_approvals([].iter()), 0);
+        assert_eq!(multisig.count_approvals([signers[0]].iter()), 1);
+        assert_eq!(multisig.count_approvals([signers[0], signers[0], signers[0]].iter()), 1);
+        assert_eq!(multisig.count_approvals([signers[2], outsider, signers[0]].iter()), 2);
+        assert_eq!(multisig.count_approvals(signers.iter()), 3);
+    }
 }
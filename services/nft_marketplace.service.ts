@@ -1,7 +1,7 @@
 import { sendRawTransaction2, sendTransaction2, TransactionLog } from "@coin98/solana-support-library";
-import { Connection, Keypair, PublicKey, Transaction } from "@solana/web3.js";
+import { AccountMeta, Connection, Keypair, PublicKey, Transaction, TransactionInstruction } from "@solana/web3.js";
 import BN from "bn.js";
-import { Collection, Creator, DataV2, NftMarketplaceInstructionService } from "./nft_marketplace_instruction.service";
+import { BatchListItem, BundleListItem, BundleSaleItem, Collection, Creator, DataV2, FeeSplit, FeeTier, FeeTierAsset, NftMarketplaceInstructionService, RoyaltyPolicy, SweepItem, SwapAsset, SwapLeg, TokenGate } from "./nft_marketplace_instruction.service";
 
 export class NftMarketplaceService {
 
@@ -123,125 +123,1102 @@ export class NftMarketplaceService {
     splTokenMint: PublicKey,
     feeAccount: PublicKey,
     owner: Keypair,
+    programAsSigner: PublicKey,
+    payer: Keypair,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    sellerFeeBasisPoints: number,
+    buyerFeeBasisPoints: number = 0,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const initMarketplaceInstruction = NftMarketplaceInstructionService.initMarketplaceInstruction(
+      marketplaceAccount,
+      splTokenMint,
+      feeAccount,
+      owner.publicKey,
+      programAsSigner,
+      payer.publicKey,
+      systemProgramId,
+      nftMarketplaceProgramId,
+      sellerFeeBasisPoints,
+      buyerFeeBasisPoints,
+    );
+
+    transaction.add(initMarketplaceInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [payer, owner])
+    return txSign;
+  }
+
+  static async setAcceptedCurrency(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    splTokenMint: PublicKey,
+    feeAccount: PublicKey,
+    owner: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+    sellerFeeBasisPoints: number,
+    buyerFeeBasisPoints: number = 0,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const setAcceptedCurrencyInstruction = NftMarketplaceInstructionService.setAcceptedCurrencyInstruction(
+      marketplaceAccount,
+      splTokenMint,
+      feeAccount,
+      owner.publicKey,
+      nftMarketplaceProgramId,
+      sellerFeeBasisPoints,
+      buyerFeeBasisPoints,
+    );
+
+    transaction.add(setAcceptedCurrencyInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async setListPriceBounds(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    splTokenMint: PublicKey,
+    owner: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+    minListPrice: BN,
+    maxListPrice: BN,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const setListPriceBoundsInstruction = NftMarketplaceInstructionService.setListPriceBoundsInstruction(
+      marketplaceAccount,
+      splTokenMint,
+      owner.publicKey,
+      nftMarketplaceProgramId,
+      minListPrice,
+      maxListPrice,
+    );
+
+    transaction.add(setListPriceBoundsInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async setPriceFeed(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    splTokenMint: PublicKey,
+    owner: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+    priceFeed: PublicKey | null,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const setPriceFeedInstruction = NftMarketplaceInstructionService.setPriceFeedInstruction(
+      marketplaceAccount,
+      splTokenMint,
+      owner.publicKey,
+      nftMarketplaceProgramId,
+      priceFeed,
+    );
+
+    transaction.add(setPriceFeedInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async migrateMarketplace(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    payer: Keypair,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const migrateMarketplaceInstruction = NftMarketplaceInstructionService.migrateMarketplaceInstruction(
+      marketplaceAccount,
+      payer.publicKey,
+      systemProgramId,
+      nftMarketplaceProgramId,
+    );
+
+    transaction.add(migrateMarketplaceInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [payer])
+    return txSign;
+  }
+
+  static async migrateListing(
+    connection: Connection,
+    sellerTradeState: PublicKey,
+    payer: Keypair,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const migrateListingInstruction = NftMarketplaceInstructionService.migrateListingInstruction(
+      sellerTradeState,
+      payer.publicKey,
+      systemProgramId,
+      nftMarketplaceProgramId,
+    );
+
+    transaction.add(migrateListingInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [payer])
+    return txSign;
+  }
+
+  static async removeAcceptedCurrency(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    splTokenMint: PublicKey,
+    feeAccount: PublicKey,
+    owner: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const removeAcceptedCurrencyInstruction = NftMarketplaceInstructionService.removeAcceptedCurrencyInstruction(
+      marketplaceAccount,
+      splTokenMint,
+      feeAccount,
+      owner.publicKey,
+      nftMarketplaceProgramId,
+    );
+
+    transaction.add(removeAcceptedCurrencyInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async setRoyaltyPolicy(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    owner: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+    royaltyPolicy: RoyaltyPolicy,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const setRoyaltyPolicyInstruction = NftMarketplaceInstructionService.setRoyaltyPolicyInstruction(
+      marketplaceAccount,
+      owner.publicKey,
+      nftMarketplaceProgramId,
+      royaltyPolicy,
+    );
+
+    transaction.add(setRoyaltyPolicyInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async setFeeTiers(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    owner: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+    feeTierAsset: FeeTierAsset | null,
+    feeTiers: FeeTier[],
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const setFeeTiersInstruction = NftMarketplaceInstructionService.setFeeTiersInstruction(
+      marketplaceAccount,
+      owner.publicKey,
+      nftMarketplaceProgramId,
+      feeTierAsset,
+      feeTiers,
+    );
+
+    transaction.add(setFeeTiersInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async setTokenGate(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    owner: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+    tokenGate: TokenGate | null,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const setTokenGateInstruction = NftMarketplaceInstructionService.setTokenGateInstruction(
+      marketplaceAccount,
+      owner.publicKey,
+      nftMarketplaceProgramId,
+      tokenGate,
+    );
+
+    transaction.add(setTokenGateInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async setReferralBasisPoints(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    owner: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+    referralBasisPoints: number,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const setReferralBasisPointsInstruction = NftMarketplaceInstructionService.setReferralBasisPointsInstruction(
+      marketplaceAccount,
+      owner.publicKey,
+      nftMarketplaceProgramId,
+      referralBasisPoints,
+    );
+
+    transaction.add(setReferralBasisPointsInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async setFeeSplits(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    owner: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+    feeSplits: FeeSplit[],
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const setFeeSplitsInstruction = NftMarketplaceInstructionService.setFeeSplitsInstruction(
+      marketplaceAccount,
+      owner.publicKey,
+      nftMarketplaceProgramId,
+      feeSplits,
+    );
+
+    transaction.add(setFeeSplitsInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async distributeFees(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    feeAccount: PublicKey,
+    owner: Keypair,
+    tokenMintAccount: PublicKey,
+    tokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    amount: BN,
+    recipientAccounts: PublicKey[],
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const distributeFeesInstruction = NftMarketplaceInstructionService.distributeFeesInstruction(
+      marketplaceAccount,
+      feeAccount,
+      owner.publicKey,
+      tokenMintAccount,
+      tokenProgramId,
+      nftMarketplaceProgramId,
+      amount,
+      recipientAccounts,
+    );
+
+    transaction.add(distributeFeesInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async setChangeDelay(
+    connection: Connection,
+    marketplaceAccount: PublicKey,
+    owner: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+    changeDelay: BN,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const setChangeDelayInstruction = NftMarketplaceInstructionService.setChangeDelayInstruction(
+      marketplaceAccount,
+      owner.publicKey,
+      nftMarketplaceProgramId,
+      changeDelay,
+    );
+
+    transaction.add(setChangeDelayInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async queuePendingChange(
+    connection: Connection,
+    pendingChange: PublicKey,
+    marketplaceAccount: PublicKey,
+    splTokenMint: PublicKey,
+    feeAccount: PublicKey,
+    owner: Keypair,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    sellerFeeBasisPoints: number,
+    buyerFeeBasisPoints: number,
+    changeDelay: BN,
+    feeSplits: FeeSplit[] | null,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const queuePendingChangeInstruction = NftMarketplaceInstructionService.queuePendingChangeInstruction(
+      pendingChange,
+      marketplaceAccount,
+      splTokenMint,
+      feeAccount,
+      owner.publicKey,
+      owner.publicKey,
+      systemProgramId,
+      nftMarketplaceProgramId,
+      sellerFeeBasisPoints,
+      buyerFeeBasisPoints,
+      changeDelay,
+      feeSplits,
+    );
+
+    transaction.add(queuePendingChangeInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async cancelPendingChange(
+    connection: Connection,
+    pendingChange: PublicKey,
+    marketplaceAccount: PublicKey,
+    owner: Keypair,
+    payer: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const cancelPendingChangeInstruction = NftMarketplaceInstructionService.cancelPendingChangeInstruction(
+      pendingChange,
+      marketplaceAccount,
+      owner.publicKey,
+      payer,
+      nftMarketplaceProgramId,
+    );
+
+    transaction.add(cancelPendingChangeInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [owner])
+    return txSign;
+  }
+
+  static async applyPendingChange(
+    connection: Connection,
+    pendingChange: PublicKey,
+    marketplaceAccount: PublicKey,
+    payer: PublicKey,
+    feePayer: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const applyPendingChangeInstruction = NftMarketplaceInstructionService.applyPendingChangeInstruction(
+      pendingChange,
+      marketplaceAccount,
+      payer,
+      nftMarketplaceProgramId,
+    );
+
+    transaction.add(applyPendingChangeInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [feePayer])
+    return txSign;
+  }
+
+  static async initCollectionStats(
+    connection: Connection,
+    collectionStats: PublicKey,
+    priceObservations: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    tokenMintAccount: PublicKey,
     payer: Keypair,
     systemProgramId: PublicKey,
     nftMarketplaceProgramId: PublicKey,
-    sellerFeeBasisPoints: number,
-    marketplaceBump: number,
-    feeBump: number,
+    collection: PublicKey,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const initCollectionStatsInstruction = NftMarketplaceInstructionService.initCollectionStatsInstruction(
+      collectionStats,
+      priceObservations,
+      nftMarketPlaceAccount,
+      tokenMintAccount,
+      payer.publicKey,
+      systemProgramId,
+      nftMarketplaceProgramId,
+      collection,
+    );
+
+    transaction.add(initCollectionStatsInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [payer])
+    return txSign;
+  }
+
+  static async initPriceObservations(
+    connection: Connection,
+    priceObservations: PublicKey,
+    collectionStats: PublicKey,
+    payer: Keypair,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+  ): Promise<[string, TransactionLog]> {
+
+    const transaction = new Transaction();
+
+    const initPriceObservationsInstruction = NftMarketplaceInstructionService.initPriceObservationsInstruction(
+      priceObservations,
+      collectionStats,
+      payer.publicKey,
+      systemProgramId,
+      nftMarketplaceProgramId,
+    );
+
+    transaction.add(initPriceObservationsInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [payer])
+    return txSign;
+  }
+
+  static async listNftToMarketplace(
+    connection: Connection,
+    sellerTradeState: PublicKey,
+    seller: Keypair,
+    mintNftAccount: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    nftTokenAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    authority: PublicKey,
+    programAsSigner: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    listPrice: BN,
+    remainingAccounts: AccountMeta[] = [],
+    usdPrice: BN | null = null,
+    priceFeed: PublicKey | null = null,
+    reservedBuyer: PublicKey | null = null,
+    tokenGate: TokenGate | null = null,
+    claimablePayout: boolean = false,
+    metadataAccount: PublicKey | null = null,
+    collectionStats: PublicKey | null = null,
+    sellerFeeTierTokenAccount: PublicKey | null = null,
+    sellerFeeTierMetadataAccount: PublicKey | null = null,
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const listNftToMarketplaceInstruction = NftMarketplaceInstructionService.listNftToMarketplaceInstruction(
+      sellerTradeState,
+      seller.publicKey,
+      mintNftAccount,
+      nftMarketPlaceAccount,
+      nftTokenAccount,
+      tokenMintAccount,
+      authority,
+      programAsSigner,
+      tokenProgramId,
+      systemProgramId,
+      nftMarketplaceProgramId,
+      listPrice,
+      remainingAccounts,
+      usdPrice,
+      priceFeed,
+      reservedBuyer,
+      tokenGate,
+      claimablePayout,
+      metadataAccount,
+      collectionStats,
+      sellerFeeTierTokenAccount,
+      sellerFeeTierMetadataAccount,
+    );
+
+    transaction.add(listNftToMarketplaceInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [seller])
+    return txSign;
+
+  }
+
+  static async cancelListing(
+    connection: Connection,
+    sellerTradeState: PublicKey,
+    seller: Keypair,
+    nftTokenAccount: PublicKey,
+    mintNftAccount: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    programAsSigner: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    collectionStats: PublicKey | null = null,
+    remainingAccounts: AccountMeta[] = [],
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const cancelListingInstruction = NftMarketplaceInstructionService.cancelListingInstruction(
+      sellerTradeState,
+      seller.publicKey,
+      nftTokenAccount,
+      mintNftAccount,
+      nftMarketPlaceAccount,
+      programAsSigner,
+      tokenProgramId,
+      systemProgramId,
+      nftMarketplaceProgramId,
+      collectionStats,
+      remainingAccounts,
+    );
+
+    transaction.add(cancelListingInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [seller])
+    return txSign;
+
+  }
+
+  static async batchList(
+    connection: Connection,
+    seller: Keypair,
+    nftMarketPlaceAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    authority: PublicKey,
+    programAsSigner: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    items: BatchListItem[],
+    sellerFeeTierTokenAccount: PublicKey | null = null,
+    sellerFeeTierMetadataAccount: PublicKey | null = null,
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const batchListInstruction = NftMarketplaceInstructionService.batchListInstruction(
+      seller.publicKey,
+      nftMarketPlaceAccount,
+      tokenMintAccount,
+      authority,
+      programAsSigner,
+      tokenProgramId,
+      systemProgramId,
+      nftMarketplaceProgramId,
+      items,
+      sellerFeeTierTokenAccount,
+      sellerFeeTierMetadataAccount,
+    );
+
+    transaction.add(batchListInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [seller])
+    return txSign;
+
+  }
+
+  static async buyNft(
+    connection: Connection,
+    buyer: Keypair,
+    seller: PublicKey,
+    buyerNftAccount: PublicKey,
+    buyerTokenAccount: PublicKey,
+    sellerTokenAccount: PublicKey | null,
+    sellerTradeState: PublicKey,
+    mintNftAccount: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    nftTokenAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    programAsSigner: PublicKey,
+    authority: PublicKey,
+    feeAccount: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    currencyTokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    amount: BN,
+    remainingAccounts: AccountMeta[] = [],
+    priceFeed: PublicKey | null = null,
+    gateTokenAccount: PublicKey | null = null,
+    gateMetadataAccount: PublicKey | null = null,
+    referrerTokenAccount: PublicKey | null = null,
+    buyerFeeTierTokenAccount: PublicKey | null = null,
+    buyerFeeTierMetadataAccount: PublicKey | null = null,
+    metadataAccount: PublicKey | null = null,
+    royaltyAccounts: PublicKey[] = [],
+    royaltyBasisPoints: number | null = null,
+    vaultTokenAccount: PublicKey | null = null,
+    sellerClaimableBalance: PublicKey | null = null,
+    collectionStats: PublicKey | null = null,
+    priceObservations: PublicKey | null = null,
   ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const buyNftFromMarketplaceInstruction = NftMarketplaceInstructionService.buyNftInstruction(
+      buyer.publicKey,
+      seller,
+      buyerNftAccount,
+      buyerTokenAccount,
+      sellerTokenAccount,
+      sellerTradeState,
+      mintNftAccount,
+      nftMarketPlaceAccount,
+      nftTokenAccount,
+      tokenMintAccount,
+      programAsSigner,
+      authority,
+      feeAccount,
+      tokenProgramId,
+      systemProgramId,
+      currencyTokenProgramId,
+      nftMarketplaceProgramId,
+      amount,
+      remainingAccounts,
+      priceFeed,
+      gateTokenAccount,
+      gateMetadataAccount,
+      referrerTokenAccount,
+      buyerFeeTierTokenAccount,
+      buyerFeeTierMetadataAccount,
+      metadataAccount,
+      royaltyAccounts,
+      royaltyBasisPoints,
+      vaultTokenAccount,
+      sellerClaimableBalance,
+      collectionStats,
+      priceObservations,
+    );
+
+    transaction.add(buyNftFromMarketplaceInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [buyer])
+    return txSign;
+
+  }
 
+  static async sweep(
+    connection: Connection,
+    buyer: Keypair,
+    buyerTokenAccount: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    programAsSigner: PublicKey,
+    authority: PublicKey,
+    feeAccount: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    currencyTokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    items: SweepItem[],
+    maxTotal: BN,
+    skipUnavailable: boolean,
+    royaltyAccounts: PublicKey[] = [],
+  ): Promise<[string, TransactionLog]> {
     const transaction = new Transaction();
 
-    const initMarketplaceInstruction = NftMarketplaceInstructionService.initMarketplaceInstruction(
-      marketplaceAccount,
-      splTokenMint,
+    const sweepInstruction = NftMarketplaceInstructionService.sweepInstruction(
+      buyer.publicKey,
+      buyerTokenAccount,
+      nftMarketPlaceAccount,
+      tokenMintAccount,
+      programAsSigner,
+      authority,
       feeAccount,
-      owner.publicKey,
-      payer.publicKey,
+      tokenProgramId,
       systemProgramId,
+      currencyTokenProgramId,
       nftMarketplaceProgramId,
-      sellerFeeBasisPoints,
-      marketplaceBump,
-      feeBump,
+      items,
+      maxTotal,
+      skipUnavailable,
+      royaltyAccounts,
     );
 
-    transaction.add(initMarketplaceInstruction);
+    transaction.add(sweepInstruction);
 
-    const txSign = await sendTransaction2(connection, transaction, [payer, owner])
+    const txSign = await sendTransaction2(connection, transaction, [buyer])
     return txSign;
+
   }
 
-  static async listNftToMarketplace(
+  static async listBundle(
     connection: Connection,
-    sellerTradeState: PublicKey,
+    bundleListing: PublicKey,
     seller: Keypair,
-    mintNftAccount: PublicKey,
     nftMarketPlaceAccount: PublicKey,
-    nftTokenAccount: PublicKey,
     tokenMintAccount: PublicKey,
     authority: PublicKey,
     programAsSigner: PublicKey,
     tokenProgramId: PublicKey,
     systemProgramId: PublicKey,
     nftMarketplaceProgramId: PublicKey,
-    listPrice: BN,
-    bump: number,
+    bundleId: BN,
+    price: BN,
+    items: BundleListItem[],
   ): Promise<[string, TransactionLog]> {
     const transaction = new Transaction();
 
-    const listNftToMarketplaceInstruction = NftMarketplaceInstructionService.listNftToMarketplaceInstruction(
-      sellerTradeState,
+    const listBundleInstruction = NftMarketplaceInstructionService.listBundleInstruction(
+      bundleListing,
       seller.publicKey,
-      mintNftAccount,
       nftMarketPlaceAccount,
-      nftTokenAccount,
       tokenMintAccount,
       authority,
       programAsSigner,
       tokenProgramId,
       systemProgramId,
       nftMarketplaceProgramId,
-      listPrice,
-      bump,
+      bundleId,
+      price,
+      items,
     );
 
-    transaction.add(listNftToMarketplaceInstruction);
+    transaction.add(listBundleInstruction);
 
     const txSign = await sendTransaction2(connection, transaction, [seller])
     return txSign;
 
   }
 
-  static async buyNft(
+  static async executeBundleSale(
     connection: Connection,
     buyer: Keypair,
     seller: PublicKey,
-    buyerNftAccount: PublicKey,
     buyerTokenAccount: PublicKey,
     sellerTokenAccount: PublicKey,
-    sellerTradeState: PublicKey,
-    mintNftAccount: PublicKey,
+    bundleListing: PublicKey,
     nftMarketPlaceAccount: PublicKey,
-    nftTokenAccount: PublicKey,
     tokenMintAccount: PublicKey,
     programAsSigner: PublicKey,
     authority: PublicKey,
     feeAccount: PublicKey,
     tokenProgramId: PublicKey,
     systemProgramId: PublicKey,
+    currencyTokenProgramId: PublicKey,
     nftMarketplaceProgramId: PublicKey,
     amount: BN,
-    bump: number,
+    items: BundleSaleItem[],
+    royaltyAccounts: PublicKey[] = [],
   ): Promise<[string, TransactionLog]> {
     const transaction = new Transaction();
 
-    const buyNftFromMarketplaceInstruction = NftMarketplaceInstructionService.buyNftInstruction(
+    const executeBundleSaleInstruction = NftMarketplaceInstructionService.executeBundleSaleInstruction(
       buyer.publicKey,
       seller,
-      buyerNftAccount,
       buyerTokenAccount,
       sellerTokenAccount,
-      sellerTradeState,
-      mintNftAccount,
+      bundleListing,
       nftMarketPlaceAccount,
-      nftTokenAccount,
       tokenMintAccount,
       programAsSigner,
       authority,
       feeAccount,
       tokenProgramId,
       systemProgramId,
+      currencyTokenProgramId,
       nftMarketplaceProgramId,
       amount,
-      bump,
+      items,
+      royaltyAccounts,
     );
 
-    transaction.add(buyNftFromMarketplaceInstruction);
+    transaction.add(executeBundleSaleInstruction);
 
     const txSign = await sendTransaction2(connection, transaction, [buyer])
     return txSign;
 
   }
 
+  static async cancelBundle(
+    connection: Connection,
+    bundleListing: PublicKey,
+    seller: Keypair,
+    nftMarketPlaceAccount: PublicKey,
+    programAsSigner: PublicKey,
+    tokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    nftTokenAccounts: PublicKey[],
+    collectionStats: (PublicKey | null)[] = [],
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const cancelBundleInstruction = NftMarketplaceInstructionService.cancelBundleInstruction(
+      bundleListing,
+      seller.publicKey,
+      nftMarketPlaceAccount,
+      programAsSigner,
+      tokenProgramId,
+      nftMarketplaceProgramId,
+      nftTokenAccounts,
+      collectionStats,
+    );
+
+    transaction.add(cancelBundleInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [seller])
+    return txSign;
+
+  }
+
+  static async createSwap(
+    connection: Connection,
+    swap: PublicKey,
+    maker: Keypair,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    swapId: BN,
+    taker: PublicKey,
+    offered: SwapAsset[],
+    requested: SwapAsset[],
+    legs: SwapLeg[],
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const createSwapInstruction = NftMarketplaceInstructionService.createSwapInstruction(
+      swap,
+      maker.publicKey,
+      tokenProgramId,
+      systemProgramId,
+      nftMarketplaceProgramId,
+      swapId,
+      taker,
+      offered,
+      requested,
+      legs,
+    );
+
+    transaction.add(createSwapInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [maker])
+    return txSign;
+
+  }
+
+  static async acceptSwap(
+    connection: Connection,
+    swap: PublicKey,
+    maker: PublicKey,
+    taker: Keypair,
+    tokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    requestedLegs: SwapLeg[],
+    offeredLegs: SwapLeg[],
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const acceptSwapInstruction = NftMarketplaceInstructionService.acceptSwapInstruction(
+      swap,
+      maker,
+      taker.publicKey,
+      tokenProgramId,
+      nftMarketplaceProgramId,
+      requestedLegs,
+      offeredLegs,
+    );
+
+    transaction.add(acceptSwapInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [taker])
+    return txSign;
+
+  }
+
+  static async cancelSwap(
+    connection: Connection,
+    swap: PublicKey,
+    maker: Keypair,
+    tokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    legs: SwapLeg[],
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const cancelSwapInstruction = NftMarketplaceInstructionService.cancelSwapInstruction(
+      swap,
+      maker.publicKey,
+      tokenProgramId,
+      nftMarketplaceProgramId,
+      legs,
+    );
+
+    transaction.add(cancelSwapInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [maker])
+    return txSign;
+
+  }
+
+  static async claim(
+    connection: Connection,
+    claimableBalance: PublicKey,
+    wallet: Keypair,
+    nftMarketPlaceAccount: PublicKey,
+    vaultTokenAccount: PublicKey,
+    destinationAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    programAsSigner: PublicKey,
+    tokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    amount: BN,
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const claimInstruction = NftMarketplaceInstructionService.claimInstruction(
+      claimableBalance,
+      wallet.publicKey,
+      nftMarketPlaceAccount,
+      vaultTokenAccount,
+      destinationAccount,
+      tokenMintAccount,
+      programAsSigner,
+      tokenProgramId,
+      nftMarketplaceProgramId,
+      amount,
+    );
+
+    transaction.add(claimInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [wallet])
+    return txSign;
+
+  }
+
+  static async createMultisig(
+    connection: Connection,
+    multisig: PublicKey,
+    creator: Keypair,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    multisigId: BN,
+    signers: PublicKey[],
+    threshold: number,
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const createMultisigInstruction = NftMarketplaceInstructionService.createMultisigInstruction(
+      multisig,
+      creator.publicKey,
+      systemProgramId,
+      nftMarketplaceProgramId,
+      multisigId,
+      signers,
+      threshold,
+    );
+
+    transaction.add(createMultisigInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [creator])
+    return txSign;
+
+  }
+
+  static async createProposal(
+    connection: Connection,
+    proposal: PublicKey,
+    multisig: PublicKey,
+    proposer: Keypair,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    instruction: TransactionInstruction,
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const createProposalInstruction = NftMarketplaceInstructionService.createProposalInstruction(
+      proposal,
+      multisig,
+      proposer.publicKey,
+      systemProgramId,
+      nftMarketplaceProgramId,
+      instruction,
+    );
+
+    transaction.add(createProposalInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [proposer])
+    return txSign;
+
+  }
+
+  static async approveProposal(
+    connection: Connection,
+    proposal: PublicKey,
+    multisig: PublicKey,
+    signer: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const approveProposalInstruction = NftMarketplaceInstructionService.approveProposalInstruction(
+      proposal,
+      multisig,
+      signer.publicKey,
+      nftMarketplaceProgramId,
+    );
+
+    transaction.add(approveProposalInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [signer])
+    return txSign;
+
+  }
+
+  static async executeProposal(
+    connection: Connection,
+    proposal: PublicKey,
+    multisig: PublicKey,
+    payer: Keypair,
+    nftMarketplaceProgramId: PublicKey,
+    instruction: TransactionInstruction,
+  ): Promise<[string, TransactionLog]> {
+    const transaction = new Transaction();
+
+    const executeProposalInstruction = NftMarketplaceInstructionService.executeProposalInstruction(
+      proposal,
+      multisig,
+      nftMarketplaceProgramId,
+      instruction,
+    );
+
+    transaction.add(executeProposalInstruction);
+
+    const txSign = await sendTransaction2(connection, transaction, [payer])
+    return txSign;
+
+  }
+
 }
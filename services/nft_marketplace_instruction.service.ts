@@ -60,18 +60,207 @@ export interface UpdateMetadataAccountRequest {
 
 export interface InitMarketplaceRequest {
   sellerFeeBasisPoints: number;
-  bump: number;
-  feeBump: number;
+  buyerFeeBasisPoints: number;
+}
+
+export interface SetAcceptedCurrencyRequest {
+  sellerFeeBasisPoints: number;
+  buyerFeeBasisPoints: number;
+}
+
+export type FeeTierAsset =
+  { token: { mint: PublicKey } } |
+  { collection: { collection: PublicKey } };
+
+export interface FeeTier {
+  minAmount: BN;
+  sellerFeeBasisPoints: number;
+  buyerFeeBasisPoints: number;
+}
+
+export type RoyaltyPolicy =
+  { enforced: {} } |
+  { minimum: { basisPoints: number } } |
+  { optional: {} };
+
+export interface SetRoyaltyPolicyRequest {
+  royaltyPolicy: RoyaltyPolicy;
+}
+
+export interface SetFeeTiersRequest {
+  feeTierAsset: FeeTierAsset | null;
+  feeTiers: FeeTier[];
+}
+
+export interface SetListPriceBoundsRequest {
+  minListPrice: BN;
+  maxListPrice: BN;
+}
+
+export interface SetPriceFeedRequest {
+  priceFeed: PublicKey | null;
+}
+
+export interface QuoteSaleRequest {
+  price: BN;
+}
+
+export type TokenGate =
+  { token: { mint: PublicKey; minAmount: BN } } |
+  { collection: { collection: PublicKey } };
+
+export interface SetTokenGateRequest {
+  tokenGate: TokenGate | null;
+}
+
+export interface SetReferralBasisPointsRequest {
+  referralBasisPoints: number;
+}
+
+export interface GetTwapRequest {
+  window: BN;
+}
+
+export interface FeeSplit {
+  recipient: PublicKey;
+  basisPoints: number;
+}
+
+export interface SetFeeSplitsRequest {
+  feeSplits: FeeSplit[];
+}
+
+export interface DistributeFeesRequest {
+  amount: BN;
+}
+
+export interface SetChangeDelayRequest {
+  changeDelay: BN;
+}
+
+export interface QueuePendingChangeRequest {
+  sellerFeeBasisPoints: number;
+  buyerFeeBasisPoints: number;
+  changeDelay: BN;
+  feeSplits: FeeSplit[] | null;
+}
+
+export interface InitCollectionStatsRequest {
+  collection: PublicKey;
 }
 
 export interface ListNftToMarketplaceRequest {
   listPrice: BN;
-  bump: number;
+  usdPrice: BN | null;
+  reservedBuyer: PublicKey | null;
+  tokenGate: TokenGate | null;
+  claimablePayout: boolean;
+}
+
+export interface BatchListRequest {
+  prices: BN[];
+}
+
+export interface BatchListItem {
+  mintNftAccount: PublicKey;
+  nftTokenAccount: PublicKey;
+  sellerTradeState: PublicKey;
+  metadataAccount: PublicKey | null;
+  collectionStats: PublicKey | null;
+  listPrice: BN;
+}
+
+export interface SweepRequest {
+  prices: BN[];
+  maxTotal: BN;
+  skipUnavailable: boolean;
+}
+
+export interface SweepItem {
+  seller: PublicKey;
+  sellerTradeState: PublicKey;
+  mintNftAccount: PublicKey;
+  nftTokenAccount: PublicKey;
+  buyerNftAccount: PublicKey;
+  sellerTokenAccount: PublicKey;
+  metadataAccount: PublicKey;
+  collectionStats: PublicKey | null;
+  priceObservations: PublicKey | null;
+  price: BN;
+}
+
+export interface ListBundleRequest {
+  bundleId: BN;
+  price: BN;
+}
+
+export interface BundleListItem {
+  mintNftAccount: PublicKey;
+  nftTokenAccount: PublicKey;
+  metadataAccount: PublicKey | null;
+  collectionStats: PublicKey | null;
+}
+
+export interface ExecuteBundleSaleRequest {
+  amount: BN;
+}
+
+export interface BundleSaleItem {
+  mintNftAccount: PublicKey;
+  nftTokenAccount: PublicKey;
+  buyerNftAccount: PublicKey;
+  metadataAccount: PublicKey;
+  collectionStats: PublicKey | null;
+  priceObservations: PublicKey | null;
+}
+
+export interface SwapAsset {
+  mint: PublicKey;
+  amount: BN;
+}
+
+export interface SwapLeg {
+  source: PublicKey;
+  destination: PublicKey;
+}
+
+export interface CreateSwapRequest {
+  swapId: BN;
+  taker: PublicKey;
+  offered: SwapAsset[];
+  requested: SwapAsset[];
+}
+
+export interface ClaimRequest {
+  amount: BN;
+}
+
+export interface CreateMultisigRequest {
+  multisigId: BN;
+  signers: PublicKey[];
+  threshold: number;
+}
+
+export interface SetMultisigSignersRequest {
+  signers: PublicKey[];
+  threshold: number;
+}
+
+export interface ProposalAccount {
+  pubkey: PublicKey;
+  isSigner: boolean;
+  isWritable: boolean;
+}
+
+export interface CreateProposalRequest {
+  instructionProgramId: PublicKey;
+  instructionAccounts: ProposalAccount[];
+  instructionData: Buffer;
 }
 
 export interface BuyNftFromMarketplaceRequest {
   amount: BN;
-  bump: number;
+  royaltyBasisPoints: number | null;
 }
 
 export class NftMarketplaceInstructionService {
@@ -199,18 +388,18 @@ export class NftMarketplaceInstructionService {
     splTokenMint: PublicKey,
     feeAccount: PublicKey,
     owner: PublicKey,
+    programAsSigner: PublicKey,
     payer: PublicKey,
     systemProgramId: PublicKey,
     nftMarketplaceProgramId: PublicKey,
     sellerFeeBasisPoints: number,
-    marketplaceBump: number,
-    feeBump: number,
+    buyerFeeBasisPoints: number = 0,
+    signers: PublicKey[] = [],
   ): TransactionInstruction {
 
     const request: InitMarketplaceRequest = {
       sellerFeeBasisPoints,
-      bump: marketplaceBump,
-      feeBump,
+      buyerFeeBasisPoints,
     }
 
     console.log('Marketplace', marketplace.toBase58());
@@ -221,9 +410,11 @@ export class NftMarketplaceInstructionService {
       <AccountMeta> { pubkey: marketplace, isSigner: false, isWritable: true },
       <AccountMeta> { pubkey: splTokenMint, isSigner: false, isWritable: true },
       <AccountMeta> { pubkey: feeAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: owner, isSigner: true, isWritable: false },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: false },
       <AccountMeta> { pubkey: payer, isSigner: true, isWritable: false },
       <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
     ]
 
     return new TransactionInstruction({
@@ -233,42 +424,187 @@ export class NftMarketplaceInstructionService {
     });
   }
 
-  static listNftToMarketplaceInstruction(
-    sellerTradeState: PublicKey,
-    seller: PublicKey,
-    mintNftAccount: PublicKey,
-    nftMarketPlaceAccount: PublicKey,
-    nftTokenAccount: PublicKey,
-    tokenMintAccount: PublicKey,
-    authority: PublicKey,
-    programAsSigner: PublicKey,
-    tokenProgramId: PublicKey,
+  static setAcceptedCurrencyInstruction(
+    marketplace: PublicKey,
+    splTokenMint: PublicKey,
+    feeAccount: PublicKey,
+    owner: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    sellerFeeBasisPoints: number,
+    buyerFeeBasisPoints: number = 0,
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: SetAcceptedCurrencyRequest = {
+      sellerFeeBasisPoints,
+      buyerFeeBasisPoints,
+    }
+
+    const data = coder.instruction.encode("setAcceptedCurrency", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: marketplace, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: splTokenMint, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: feeAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
+    ]
+
+    return new TransactionInstruction({
+      keys,
+      data,
+      programId: nftMarketplaceProgramId,
+    });
+  }
+
+  static setListPriceBoundsInstruction(
+    marketplace: PublicKey,
+    splTokenMint: PublicKey,
+    owner: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    minListPrice: BN,
+    maxListPrice: BN,
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: SetListPriceBoundsRequest = {
+      minListPrice,
+      maxListPrice,
+    }
+
+    const data = coder.instruction.encode("setListPriceBounds", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: marketplace, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: splTokenMint, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
+    ]
+
+    return new TransactionInstruction({
+      keys,
+      data,
+      programId: nftMarketplaceProgramId,
+    });
+  }
+
+  static setPriceFeedInstruction(
+    marketplace: PublicKey,
+    splTokenMint: PublicKey,
+    owner: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    priceFeed: PublicKey | null,
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: SetPriceFeedRequest = {
+      priceFeed,
+    }
+
+    const data = coder.instruction.encode("setPriceFeed", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: marketplace, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: splTokenMint, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
+    ]
+
+    return new TransactionInstruction({
+      keys,
+      data,
+      programId: nftMarketplaceProgramId,
+    });
+  }
+
+  static migrateMarketplaceInstruction(
+    marketplace: PublicKey,
+    payer: PublicKey,
     systemProgramId: PublicKey,
     nftMarketplaceProgramId: PublicKey,
-    listPrice: BN,
-    bump: number,
   ): TransactionInstruction {
 
-    const request: ListNftToMarketplaceRequest = {
-      listPrice,
-      bump,
-    };
+    const data = coder.instruction.encode("migrateMarketplace", {})
 
-    console.log('list price', listPrice.toString());
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: marketplace, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: payer, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+    ]
 
-    const data = coder.instruction.encode("listNftToMarketplace", request)
+    return new TransactionInstruction({
+      keys,
+      data,
+      programId: nftMarketplaceProgramId,
+    });
+  }
+
+  static migrateListingInstruction(
+    sellerTradeState: PublicKey,
+    payer: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+  ): TransactionInstruction {
+
+    const data = coder.instruction.encode("migrateListing", {})
 
     const keys: AccountMeta[] = [
       <AccountMeta> { pubkey: sellerTradeState, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: seller, isSigner: true, isWritable: false },
-      <AccountMeta> { pubkey: mintNftAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: nftTokenAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: authority, isSigner: false, isWritable: false },
-      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: payer, isSigner: true, isWritable: true },
       <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+    ]
+
+    return new TransactionInstruction({
+      keys,
+      data,
+      programId: nftMarketplaceProgramId,
+    });
+  }
+
+  static removeAcceptedCurrencyInstruction(
+    marketplace: PublicKey,
+    splTokenMint: PublicKey,
+    feeAccount: PublicKey,
+    owner: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const data = coder.instruction.encode("removeAcceptedCurrency", {})
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: marketplace, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: splTokenMint, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: feeAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
+    ]
+
+    return new TransactionInstruction({
+      keys,
+      data,
+      programId: nftMarketplaceProgramId,
+    });
+  }
+
+  static setRoyaltyPolicyInstruction(
+    marketplaceAccount: PublicKey,
+    owner: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    royaltyPolicy: RoyaltyPolicy,
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: SetRoyaltyPolicyRequest = {
+      royaltyPolicy,
+    };
+
+    const data = coder.instruction.encode("setRoyaltyPolicy", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: marketplaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
     ];
     return new TransactionInstruction(
       {
@@ -279,56 +615,1117 @@ export class NftMarketplaceInstructionService {
     );
   }
 
-  static buyNftInstruction(
-    buyer: PublicKey,
-    seller: PublicKey,
-    buyerNftAccount: PublicKey,
-    buyerTokenAccount: PublicKey,
-    sellerTokenAccount: PublicKey,
-    sellerTradeState: PublicKey,
-    mintNftAccount: PublicKey,
-    nftMarketPlaceAccount: PublicKey,
-    nftTokenAccount: PublicKey,
-    tokenMintAccount: PublicKey,
-    programAsSigner: PublicKey,
-    authority: PublicKey,
-    feeAccount: PublicKey,
-    tokenProgramId: PublicKey,
-    systemProgramId: PublicKey,
+  static setFeeTiersInstruction(
+    marketplaceAccount: PublicKey,
+    owner: PublicKey,
     nftMarketplaceProgramId: PublicKey,
-    amount: BN,
-    bump: number,
+    feeTierAsset: FeeTierAsset | null,
+    feeTiers: FeeTier[],
+    signers: PublicKey[] = [],
   ): TransactionInstruction {
-    const request: BuyNftFromMarketplaceRequest = {
-      amount,
-      bump,
+
+    const request: SetFeeTiersRequest = {
+      feeTierAsset,
+      feeTiers,
     };
 
-    const data = coder.instruction.encode("executeSale", request)
+    const data = coder.instruction.encode("setFeeTiers", request)
 
     const keys: AccountMeta[] = [
-      <AccountMeta> { pubkey: buyer, isSigner: true, isWritable: false },
-      <AccountMeta> { pubkey: seller, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: buyerNftAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: buyerTokenAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: sellerTokenAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: sellerTradeState, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: mintNftAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: nftTokenAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: authority, isSigner: false, isWritable: false },
-      <AccountMeta> { pubkey: feeAccount, isSigner: false, isWritable: true },
-      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
-      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: marketplaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
     ];
     return new TransactionInstruction(
       {
         keys,
         data,
         programId: nftMarketplaceProgramId,
-      });
+      }
+    );
+  }
+
+  static setFeeSplitsInstruction(
+    marketplaceAccount: PublicKey,
+    owner: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    feeSplits: FeeSplit[],
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: SetFeeSplitsRequest = {
+      feeSplits,
+    };
+
+    const data = coder.instruction.encode("setFeeSplits", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: marketplaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  // `recipientAccounts` are token accounts of the fee split recipients, in the order of the table
+  static distributeFeesInstruction(
+    marketplaceAccount: PublicKey,
+    feeAccount: PublicKey,
+    owner: PublicKey,
+    tokenMintAccount: PublicKey,
+    tokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    amount: BN,
+    recipientAccounts: PublicKey[],
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: DistributeFeesRequest = {
+      amount,
+    };
+
+    const data = coder.instruction.encode("distributeFees", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: marketplaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: feeAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      ...recipientAccounts.map(recipientAccount => <AccountMeta> { pubkey: recipientAccount, isSigner: false, isWritable: true }),
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static setChangeDelayInstruction(
+    marketplaceAccount: PublicKey,
+    owner: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    changeDelay: BN,
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: SetChangeDelayRequest = {
+      changeDelay,
+    };
+
+    const data = coder.instruction.encode("setChangeDelay", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: marketplaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static queuePendingChangeInstruction(
+    pendingChange: PublicKey,
+    marketplaceAccount: PublicKey,
+    splTokenMint: PublicKey,
+    feeAccount: PublicKey,
+    owner: PublicKey,
+    payer: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    sellerFeeBasisPoints: number,
+    buyerFeeBasisPoints: number,
+    changeDelay: BN,
+    feeSplits: FeeSplit[] | null,
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: QueuePendingChangeRequest = {
+      sellerFeeBasisPoints,
+      buyerFeeBasisPoints,
+      changeDelay,
+      feeSplits,
+    };
+
+    const data = coder.instruction.encode("queuePendingChange", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: pendingChange, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: marketplaceAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: splTokenMint, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: feeAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      <AccountMeta> { pubkey: payer, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static cancelPendingChangeInstruction(
+    pendingChange: PublicKey,
+    marketplaceAccount: PublicKey,
+    owner: PublicKey,
+    payer: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const data = coder.instruction.encode("cancelPendingChange", {})
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: pendingChange, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: marketplaceAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      <AccountMeta> { pubkey: payer, isSigner: false, isWritable: true },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static applyPendingChangeInstruction(
+    pendingChange: PublicKey,
+    marketplaceAccount: PublicKey,
+    payer: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+  ): TransactionInstruction {
+
+    const data = coder.instruction.encode("applyPendingChange", {})
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: pendingChange, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: marketplaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: payer, isSigner: false, isWritable: true },
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static initCollectionStatsInstruction(
+    collectionStats: PublicKey,
+    priceObservations: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    payer: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    collection: PublicKey,
+  ): TransactionInstruction {
+
+    const request: InitCollectionStatsRequest = {
+      collection,
+    };
+
+    const data = coder.instruction.encode("initCollectionStats", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: collectionStats, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: priceObservations, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: payer, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static initPriceObservationsInstruction(
+    priceObservations: PublicKey,
+    collectionStats: PublicKey,
+    payer: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+  ): TransactionInstruction {
+
+    const data = coder.instruction.encode("initPriceObservations", {})
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: priceObservations, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: collectionStats, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: payer, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static getTwapInstruction(
+    priceObservations: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    window: BN,
+  ): TransactionInstruction {
+
+    const request: GetTwapRequest = {
+      window,
+    };
+
+    const data = coder.instruction.encode("getTwap", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: priceObservations, isSigner: false, isWritable: false },
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static quoteSaleInstruction(
+    nftMarketPlaceAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    price: BN,
+  ): TransactionInstruction {
+
+    const request: QuoteSaleRequest = {
+      price,
+    };
+
+    const data = coder.instruction.encode("quoteSale", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: false },
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static setTokenGateInstruction(
+    marketplaceAccount: PublicKey,
+    owner: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    tokenGate: TokenGate | null,
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: SetTokenGateRequest = {
+      tokenGate,
+    };
+
+    const data = coder.instruction.encode("setTokenGate", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: marketplaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static setReferralBasisPointsInstruction(
+    marketplaceAccount: PublicKey,
+    owner: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    referralBasisPoints: number,
+    signers: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: SetReferralBasisPointsRequest = {
+      referralBasisPoints,
+    };
+
+    const data = coder.instruction.encode("setReferralBasisPoints", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: marketplaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: owner, isSigner: !signers.length, isWritable: false },
+      ...signers.map(signer => <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false }),
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static listNftToMarketplaceInstruction(
+    sellerTradeState: PublicKey,
+    seller: PublicKey,
+    mintNftAccount: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    nftTokenAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    authority: PublicKey,
+    programAsSigner: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    listPrice: BN,
+    remainingAccounts: AccountMeta[] = [],
+    usdPrice: BN | null = null,
+    priceFeed: PublicKey | null = null,
+    reservedBuyer: PublicKey | null = null,
+    tokenGate: TokenGate | null = null,
+    claimablePayout: boolean = false,
+    metadataAccount: PublicKey | null = null,
+    collectionStats: PublicKey | null = null,
+    sellerFeeTierTokenAccount: PublicKey | null = null,
+    sellerFeeTierMetadataAccount: PublicKey | null = null,
+  ): TransactionInstruction {
+
+    const request: ListNftToMarketplaceRequest = {
+      listPrice,
+      usdPrice,
+      reservedBuyer,
+      tokenGate,
+      claimablePayout,
+    };
+
+    console.log('list price', listPrice.toString());
+
+    const data = coder.instruction.encode("listNftToMarketplace", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: sellerTradeState, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: seller, isSigner: true, isWritable: false },
+      <AccountMeta> { pubkey: mintNftAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: nftTokenAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: authority, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: priceFeed || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: metadataAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: collectionStats || nftMarketplaceProgramId, isSigner: false, isWritable: !!collectionStats },
+      <AccountMeta> { pubkey: sellerFeeTierTokenAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: sellerFeeTierMetadataAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+      ...remainingAccounts,
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static cancelListingInstruction(
+    sellerTradeState: PublicKey,
+    seller: PublicKey,
+    nftTokenAccount: PublicKey,
+    mintNftAccount: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    programAsSigner: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    collectionStats: PublicKey | null = null,
+    remainingAccounts: AccountMeta[] = [],
+  ): TransactionInstruction {
+
+    const data = coder.instruction.encode("cancelListing", {})
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: sellerTradeState, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: seller, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: nftTokenAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: mintNftAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: collectionStats || nftMarketplaceProgramId, isSigner: false, isWritable: !!collectionStats },
+      ...remainingAccounts,
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static batchListInstruction(
+    seller: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    authority: PublicKey,
+    programAsSigner: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    items: BatchListItem[],
+    sellerFeeTierTokenAccount: PublicKey | null = null,
+    sellerFeeTierMetadataAccount: PublicKey | null = null,
+  ): TransactionInstruction {
+
+    const request: BatchListRequest = {
+      prices: items.map(item => item.listPrice),
+    };
+
+    const data = coder.instruction.encode("batchList", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: seller, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: authority, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: sellerFeeTierTokenAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: sellerFeeTierMetadataAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+    ];
+    for (const item of items) {
+      keys.push(
+        <AccountMeta> { pubkey: item.mintNftAccount, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.nftTokenAccount, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.sellerTradeState, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.metadataAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+        <AccountMeta> { pubkey: item.collectionStats || nftMarketplaceProgramId, isSigner: false, isWritable: !!item.collectionStats },
+      );
+    }
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static buyNftInstruction(
+    buyer: PublicKey,
+    seller: PublicKey,
+    buyerNftAccount: PublicKey,
+    buyerTokenAccount: PublicKey,
+    sellerTokenAccount: PublicKey | null,
+    sellerTradeState: PublicKey,
+    mintNftAccount: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    nftTokenAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    programAsSigner: PublicKey,
+    authority: PublicKey,
+    feeAccount: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    currencyTokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    amount: BN,
+    remainingAccounts: AccountMeta[] = [],
+    priceFeed: PublicKey | null = null,
+    gateTokenAccount: PublicKey | null = null,
+    gateMetadataAccount: PublicKey | null = null,
+    referrerTokenAccount: PublicKey | null = null,
+    buyerFeeTierTokenAccount: PublicKey | null = null,
+    buyerFeeTierMetadataAccount: PublicKey | null = null,
+    metadataAccount: PublicKey | null = null,
+    royaltyAccounts: PublicKey[] = [],
+    royaltyBasisPoints: number | null = null,
+    vaultTokenAccount: PublicKey | null = null,
+    sellerClaimableBalance: PublicKey | null = null,
+    collectionStats: PublicKey | null = null,
+    priceObservations: PublicKey | null = null,
+  ): TransactionInstruction {
+    const request: BuyNftFromMarketplaceRequest = {
+      amount,
+      royaltyBasisPoints,
+    };
+
+    const data = coder.instruction.encode("executeSale", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: buyer, isSigner: true, isWritable: false },
+      <AccountMeta> { pubkey: seller, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: buyerNftAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: buyerTokenAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: sellerTokenAccount || nftMarketplaceProgramId, isSigner: false, isWritable: !!sellerTokenAccount },
+      <AccountMeta> { pubkey: sellerTradeState, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: mintNftAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: nftTokenAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: authority, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: feeAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: currencyTokenProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: priceFeed || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: gateTokenAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: gateMetadataAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: referrerTokenAccount || nftMarketplaceProgramId, isSigner: false, isWritable: !!referrerTokenAccount },
+      <AccountMeta> { pubkey: buyerFeeTierTokenAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: buyerFeeTierMetadataAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: metadataAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: vaultTokenAccount || nftMarketplaceProgramId, isSigner: false, isWritable: !!vaultTokenAccount },
+      <AccountMeta> { pubkey: sellerClaimableBalance || nftMarketplaceProgramId, isSigner: false, isWritable: !!sellerClaimableBalance },
+      <AccountMeta> { pubkey: collectionStats || nftMarketplaceProgramId, isSigner: false, isWritable: !!collectionStats },
+      <AccountMeta> { pubkey: priceObservations || nftMarketplaceProgramId, isSigner: false, isWritable: !!priceObservations },
+      ...remainingAccounts,
+      ...royaltyAccounts.map(royaltyAccount => <AccountMeta> { pubkey: royaltyAccount, isSigner: false, isWritable: true }),
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      });
+  }
+
+  static sweepInstruction(
+    buyer: PublicKey,
+    buyerTokenAccount: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    programAsSigner: PublicKey,
+    authority: PublicKey,
+    feeAccount: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    currencyTokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    items: SweepItem[],
+    maxTotal: BN,
+    skipUnavailable: boolean,
+    royaltyAccounts: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: SweepRequest = {
+      prices: items.map(item => item.price),
+      maxTotal,
+      skipUnavailable,
+    };
+
+    const data = coder.instruction.encode("sweep", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: buyer, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: buyerTokenAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: authority, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: feeAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: currencyTokenProgramId, isSigner: false, isWritable: false },
+    ];
+    for (const item of items) {
+      keys.push(
+        <AccountMeta> { pubkey: item.seller, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.sellerTradeState, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.mintNftAccount, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.nftTokenAccount, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.buyerNftAccount, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.sellerTokenAccount, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.metadataAccount, isSigner: false, isWritable: false },
+        <AccountMeta> { pubkey: item.collectionStats || nftMarketplaceProgramId, isSigner: false, isWritable: !!item.collectionStats },
+        <AccountMeta> { pubkey: item.priceObservations || nftMarketplaceProgramId, isSigner: false, isWritable: !!item.priceObservations },
+      );
+    }
+    for (const royaltyAccount of royaltyAccounts) {
+      keys.push(<AccountMeta> { pubkey: royaltyAccount, isSigner: false, isWritable: true });
+    }
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static listBundleInstruction(
+    bundleListing: PublicKey,
+    seller: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    authority: PublicKey,
+    programAsSigner: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    bundleId: BN,
+    price: BN,
+    items: BundleListItem[],
+  ): TransactionInstruction {
+
+    const request: ListBundleRequest = {
+      bundleId,
+      price,
+    };
+
+    const data = coder.instruction.encode("listBundle", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: bundleListing, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: seller, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: authority, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+    ];
+    for (const item of items) {
+      keys.push(
+        <AccountMeta> { pubkey: item.mintNftAccount, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.nftTokenAccount, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.metadataAccount || nftMarketplaceProgramId, isSigner: false, isWritable: false },
+        <AccountMeta> { pubkey: item.collectionStats || nftMarketplaceProgramId, isSigner: false, isWritable: !!item.collectionStats },
+      );
+    }
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static executeBundleSaleInstruction(
+    buyer: PublicKey,
+    seller: PublicKey,
+    buyerTokenAccount: PublicKey,
+    sellerTokenAccount: PublicKey,
+    bundleListing: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    programAsSigner: PublicKey,
+    authority: PublicKey,
+    feeAccount: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    currencyTokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    amount: BN,
+    items: BundleSaleItem[],
+    royaltyAccounts: PublicKey[] = [],
+  ): TransactionInstruction {
+
+    const request: ExecuteBundleSaleRequest = {
+      amount,
+    };
+
+    const data = coder.instruction.encode("executeBundleSale", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: buyer, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: seller, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: buyerTokenAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: sellerTokenAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: bundleListing, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: authority, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: feeAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: currencyTokenProgramId, isSigner: false, isWritable: false },
+    ];
+    for (const item of items) {
+      keys.push(
+        <AccountMeta> { pubkey: item.mintNftAccount, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.nftTokenAccount, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.buyerNftAccount, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: item.metadataAccount, isSigner: false, isWritable: false },
+        <AccountMeta> { pubkey: item.collectionStats || nftMarketplaceProgramId, isSigner: false, isWritable: !!item.collectionStats },
+        <AccountMeta> { pubkey: item.priceObservations || nftMarketplaceProgramId, isSigner: false, isWritable: !!item.priceObservations },
+      );
+    }
+    for (const royaltyAccount of royaltyAccounts) {
+      keys.push(<AccountMeta> { pubkey: royaltyAccount, isSigner: false, isWritable: true });
+    }
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static cancelBundleInstruction(
+    bundleListing: PublicKey,
+    seller: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    programAsSigner: PublicKey,
+    tokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    nftTokenAccounts: PublicKey[],
+    collectionStats: (PublicKey | null)[] = [],
+  ): TransactionInstruction {
+
+    const data = coder.instruction.encode("cancelBundle", {})
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: bundleListing, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: seller, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+    ];
+    for (let i = 0; i < nftTokenAccounts.length; i++) {
+      keys.push(
+        <AccountMeta> { pubkey: nftTokenAccounts[i], isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: collectionStats[i] || nftMarketplaceProgramId, isSigner: false, isWritable: !!collectionStats[i] },
+      );
+    }
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static createSwapInstruction(
+    swap: PublicKey,
+    maker: PublicKey,
+    tokenProgramId: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    swapId: BN,
+    taker: PublicKey,
+    offered: SwapAsset[],
+    requested: SwapAsset[],
+    legs: SwapLeg[],
+  ): TransactionInstruction {
+
+    const request: CreateSwapRequest = {
+      swapId,
+      taker,
+      offered,
+      requested,
+    };
+
+    const data = coder.instruction.encode("createSwap", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: swap, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: maker, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+    ];
+    for (const leg of legs) {
+      keys.push(
+        <AccountMeta> { pubkey: leg.source, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: leg.destination, isSigner: false, isWritable: true },
+      );
+    }
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static acceptSwapInstruction(
+    swap: PublicKey,
+    maker: PublicKey,
+    taker: PublicKey,
+    tokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    requestedLegs: SwapLeg[],
+    offeredLegs: SwapLeg[],
+  ): TransactionInstruction {
+
+    const data = coder.instruction.encode("acceptSwap", {})
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: swap, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: maker, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: taker, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+    ];
+    const legs = [...requestedLegs, ...offeredLegs];
+    for (const leg of legs) {
+      keys.push(
+        <AccountMeta> { pubkey: leg.source, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: leg.destination, isSigner: false, isWritable: true },
+      );
+    }
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static cancelSwapInstruction(
+    swap: PublicKey,
+    maker: PublicKey,
+    tokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    legs: SwapLeg[],
+  ): TransactionInstruction {
+
+    const data = coder.instruction.encode("cancelSwap", {})
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: swap, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: maker, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+    ];
+    for (const leg of legs) {
+      keys.push(
+        <AccountMeta> { pubkey: leg.source, isSigner: false, isWritable: true },
+        <AccountMeta> { pubkey: leg.destination, isSigner: false, isWritable: true },
+      );
+    }
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static claimInstruction(
+    claimableBalance: PublicKey,
+    wallet: PublicKey,
+    nftMarketPlaceAccount: PublicKey,
+    vaultTokenAccount: PublicKey,
+    destinationAccount: PublicKey,
+    tokenMintAccount: PublicKey,
+    programAsSigner: PublicKey,
+    tokenProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    amount: BN,
+  ): TransactionInstruction {
+
+    const request: ClaimRequest = {
+      amount,
+    };
+
+    const data = coder.instruction.encode("claim", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: claimableBalance, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: wallet, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: nftMarketPlaceAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: vaultTokenAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: destinationAccount, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: tokenMintAccount, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: programAsSigner, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: tokenProgramId, isSigner: false, isWritable: false },
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static createMultisigInstruction(
+    multisig: PublicKey,
+    creator: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    multisigId: BN,
+    signers: PublicKey[],
+    threshold: number,
+  ): TransactionInstruction {
+
+    const request: CreateMultisigRequest = {
+      multisigId,
+      signers,
+      threshold,
+    };
+
+    const data = coder.instruction.encode("createMultisig", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: multisig, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: creator, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  // Only executable through a proposal of the multisig
+  static setMultisigSignersInstruction(
+    multisig: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    signers: PublicKey[],
+    threshold: number,
+  ): TransactionInstruction {
+
+    const request: SetMultisigSignersRequest = {
+      signers,
+      threshold,
+    };
+
+    const data = coder.instruction.encode("setMultisigSigners", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: multisig, isSigner: true, isWritable: true },
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static createProposalInstruction(
+    proposal: PublicKey,
+    multisig: PublicKey,
+    proposer: PublicKey,
+    systemProgramId: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    instruction: TransactionInstruction,
+  ): TransactionInstruction {
+
+    const request: CreateProposalRequest = {
+      instructionProgramId: instruction.programId,
+      instructionAccounts: instruction.keys.map(key => <ProposalAccount> {
+        pubkey: key.pubkey,
+        isSigner: key.isSigner,
+        isWritable: key.isWritable,
+      }),
+      instructionData: instruction.data,
+    };
+
+    const data = coder.instruction.encode("createProposal", request)
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: proposal, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: multisig, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: proposer, isSigner: true, isWritable: true },
+      <AccountMeta> { pubkey: systemProgramId, isSigner: false, isWritable: false },
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
+
+  static approveProposalInstruction(
+    proposal: PublicKey,
+    multisig: PublicKey,
+    signer: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+  ): TransactionInstruction {
+
+    const data = coder.instruction.encode("approveProposal", {})
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: proposal, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: multisig, isSigner: false, isWritable: false },
+      <AccountMeta> { pubkey: signer, isSigner: true, isWritable: false },
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
   }
 
+  // `instruction` is the proposed instruction, its accounts and program are passed for the call
+  static executeProposalInstruction(
+    proposal: PublicKey,
+    multisig: PublicKey,
+    nftMarketplaceProgramId: PublicKey,
+    instruction: TransactionInstruction,
+  ): TransactionInstruction {
+
+    const data = coder.instruction.encode("executeProposal", {})
+
+    const keys: AccountMeta[] = [
+      <AccountMeta> { pubkey: proposal, isSigner: false, isWritable: true },
+      <AccountMeta> { pubkey: multisig, isSigner: false, isWritable: false },
+      ...instruction.keys.map(key => <AccountMeta> {
+        pubkey: key.pubkey,
+        isSigner: false,
+        isWritable: key.isWritable,
+      }),
+      <AccountMeta> { pubkey: instruction.programId, isSigner: false, isWritable: false },
+    ];
+    return new TransactionInstruction(
+      {
+        keys,
+        data,
+        programId: nftMarketplaceProgramId,
+      }
+    );
+  }
 }
@@ -5,10 +5,11 @@ import { sendTransaction, TOKEN_PROGRAM_ID } from "@coin98/solana-support-librar
 import { Account, createMint, getAccount, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
 import { Connection, Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
 import { Metadata } from "@metaplex-foundation/mpl-token-metadata";
-import { NftMarketplaceService } from "../services";
+import { NftMarketplaceInstructionService, NftMarketplaceService } from "../services";
 import { BN } from "bn.js";
-import { DataV2 } from "../services/nft_marketplace_instruction.service";
+import { BatchListItem, BundleListItem, BundleSaleItem, DataV2, SweepItem } from "../services/nft_marketplace_instruction.service";
 import { findProgramAddressSync } from "@project-serum/anchor/dist/cjs/utils/pubkey";
+import { expect } from "chai";
 
 describe("nft-collection", () => {
   // Configure the client to use the local cluster.
@@ -22,6 +23,7 @@ describe("nft-collection", () => {
   const SYSVAR_RENT_PUBKEY = new PublicKey("SysvarRent111111111111111111111111111111111");
   const NFT_MARKETPLACE_PROGRAM_ID: PublicKey = new PublicKey('H4Theeu9v5WwLSSUc9BTtCehCgw2ap6KxekeQkcbgBJz');
   const SPL_TOKEN_PROGRAM_ID: PublicKey = new PublicKey('TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA')
+  const MOCK_PRICE_FEED: PublicKey = new PublicKey('G8ydYtd23nmUEQSRqRJhUQgjsMnCktJZ45HzQAQWZRFi');
 
   // Root public key and keypair
   let root: anchor.web3.Keypair;
@@ -43,22 +45,120 @@ describe("nft-collection", () => {
 
   // Marketplace accounts
   let marketplaceAccount: PublicKey;
-  let marketplaceBump: number;
   let feeAccount: PublicKey;
-  let feeBump: number;
   let feeATA: Account;
 
   // Delegate account
   let programAsSigner: anchor.web3.PublicKey;
-  let programAsSignerBump: number;
 
   // Sell - Buy account
   let sellerTradeState: anchor.web3.PublicKey;
-  let sellerTradeStateBump: number;
   let buyerNftATA: Account;
   let buyerMTTATA: Account;
   let sellerMTTATA: Account;
 
+  // Batch listing accounts
+  let batchNfts: { mint: PublicKey, tokenAccount: PublicKey, metadataAccount: PublicKey }[];
+  let batchListItems: BatchListItem[];
+
+  // Mint a new NFT to root, with root as its verified creator
+  async function createNft(): Promise<{ mint: PublicKey, tokenAccount: PublicKey, metadataAccount: PublicKey }> {
+    const nftMint = await createMint(
+      connection,
+      root,
+      root.publicKey,
+      root.publicKey,
+      0,
+    );
+    const nftATA = await getOrCreateAssociatedTokenAccount(
+      connection,
+      root,
+      nftMint,
+      root.publicKey,
+    );
+    await mintTo(
+      connection,
+      root,
+      nftMint,
+      nftATA.address,
+      root.publicKey,
+      1
+    );
+
+    const [nftMetadataAccount] = findProgramAddressSync(
+      [
+        Buffer.from('metadata'),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        nftMint.toBuffer(),
+      ],
+      TOKEN_METADATA_PROGRAM_ID,
+    );
+    await NftMarketplaceService.createMetadataAccount(
+      connection,
+      nftMetadataAccount,
+      nftMint,
+      root, // mintAuthority
+      root, // payer
+      root, // updateAuthority
+      SystemProgram.programId,
+      SYSVAR_RENT_PUBKEY,
+      TOKEN_METADATA_PROGRAM_ID,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      "Hello NFT",
+      "HNFT",
+      "https://raw.githubusercontent.com/Coding-and-Crypto/Solana-NFT-Marketplace/master/assets/example.json",
+      [
+        {
+          address: root.publicKey,
+          verified: true,
+          share: 100,
+        }
+      ],
+    );
+
+    const [nftMasterEditionAccount] = findProgramAddressSync(
+      [
+        Buffer.from('metadata'),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        nftMint.toBuffer(),
+        Buffer.from('edition'),
+      ],
+      TOKEN_METADATA_PROGRAM_ID,
+    );
+    await NftMarketplaceService.createMasterEditionAccount(
+      connection,
+      nftMasterEditionAccount,
+      nftMetadataAccount,
+      nftMint,
+      root, // mintAuthority
+      root, // payer
+      root, // updateAuthority
+      SystemProgram.programId,
+      SYSVAR_RENT_PUBKEY,
+      TOKEN_METADATA_PROGRAM_ID,
+      SPL_TOKEN_PROGRAM_ID,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      new BN(1)
+    );
+
+    return { mint: nftMint, tokenAccount: nftATA.address, metadataAccount: nftMetadataAccount };
+  }
+
+  function findListingAddress(seller: PublicKey, listPrice: BN, nftMint: PublicKey, nftTokenAccount: PublicKey): PublicKey {
+    return findProgramAddressSync(
+      [
+        Buffer.from("MARKETPLACE_LISTING"),
+        seller.toBuffer(),
+        Buffer.from([(listPrice)]),
+        nftMint.toBuffer(),
+        marketplaceAccount.toBuffer(),
+        nftTokenAccount.toBuffer(),
+        mintMTT.toBuffer(),
+      ],
+      NFT_MARKETPLACE_PROGRAM_ID,
+    )[0];
+  }
+
 
   before(async () => {
     console.log('Creating root account...');
@@ -236,7 +336,7 @@ describe("nft-collection", () => {
   })
 
   it('Init marketplace', async () => {
-    [marketplaceAccount, marketplaceBump] = findProgramAddressSync(
+    [marketplaceAccount] = findProgramAddressSync(
       [
         Buffer.from("MARKETPLACE"),
         root.publicKey.toBuffer(),
@@ -257,25 +357,34 @@ describe("nft-collection", () => {
 
     console.log('Fee account: ', feeATA.address.toBase58());
 
+    [programAsSigner] = findProgramAddressSync(
+      [
+        Buffer.from("MARKETPLACE"),
+        marketplaceAccount.toBuffer(),
+        Buffer.from("MARKETPLACE_SIGNER"),
+      ],
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+    console.log('Program as signer: ', programAsSigner.toBase58());
+
     const initMarketplaceTx = await NftMarketplaceService.initMarketplace(
       connection,
       marketplaceAccount,
       mintMTT,
       feeATA.address,
       root, // owner
+      programAsSigner,
       root, // payer
       SystemProgram.programId,
       NFT_MARKETPLACE_PROGRAM_ID,
       200,
-      marketplaceBump,
-      feeBump,
     );
     console.log('Marketplace initialized: ', initMarketplaceTx);
   });
 
   it('List NFT to marketplace', async () => {
     const listPrice = new BN(1000 * LAMPORTS_PER_SOL);
-    [sellerTradeState, sellerTradeStateBump] = findProgramAddressSync(
+    [sellerTradeState] = findProgramAddressSync(
       [
         Buffer.from("MARKETPLACE_LISTING"),
         root.publicKey.toBuffer(),
@@ -290,15 +399,6 @@ describe("nft-collection", () => {
 
     console.log('Seller trade state: ', sellerTradeState.toBase58());
 
-    [programAsSigner, programAsSignerBump] = findProgramAddressSync(
-      [
-        Buffer.from("MARKETPLACE"),
-        Buffer.from("MARKETPLACE_SIGNER"),
-      ],
-      NFT_MARKETPLACE_PROGRAM_ID,
-    );
-    console.log('Program as signer: ', programAsSigner.toBase58());
-
     const listNftTx = await NftMarketplaceService.listNftToMarketplace(
       connection,
       sellerTradeState,
@@ -313,7 +413,13 @@ describe("nft-collection", () => {
       SystemProgram.programId,
       NFT_MARKETPLACE_PROGRAM_ID,
       listPrice,
-      sellerTradeStateBump,
+      [],
+      null,
+      null,
+      null,
+      null,
+      false,
+      metadataAccount,
     );
 
     console.log('NFT listed to marketplace: ', listNftTx);
@@ -377,9 +483,18 @@ describe("nft-collection", () => {
       feeATA.address,
       TOKEN_PROGRAM_ID,
       SystemProgram.programId,
+      TOKEN_PROGRAM_ID,
       NFT_MARKETPLACE_PROGRAM_ID,
       new BN(1000 * LAMPORTS_PER_SOL),
-      programAsSignerBump,
+      [],
+      null,
+      null,
+      null,
+      null,
+      null,
+      null,
+      metadataAccount,
+      [sellerMTTATA.address],
     );
 
     console.log('NFT bought: ', buyNftTx);
@@ -387,6 +502,686 @@ describe("nft-collection", () => {
 
   });
 
+  it('Trust mock price feed for MTT', async () => {
+    // Loaded by the local validator from tests/fixtures/mock_price_feed.json
+    const setPriceFeedTx = await NftMarketplaceService.setPriceFeed(
+      connection,
+      marketplaceAccount,
+      mintMTT,
+      root, // owner
+      NFT_MARKETPLACE_PROGRAM_ID,
+      MOCK_PRICE_FEED,
+    );
+    console.log('Price feed trusted: ', setPriceFeedTx);
+  });
+
+  it('List NFT pegged to USD', async () => {
+    const listPrice = new BN(2 * LAMPORTS_PER_SOL);
+    const [usdSellerTradeState] = findProgramAddressSync(
+      [
+        Buffer.from("MARKETPLACE_LISTING"),
+        user2.publicKey.toBuffer(),
+        Buffer.from([(listPrice)]),
+        mint.toBuffer(),
+        marketplaceAccount.toBuffer(),
+        buyerNftATA.address.toBuffer(),
+        mintMTT.toBuffer(),
+      ],
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+
+    // 300 USD, settled at 150 USD per MTT by the mock feed
+    const listNftTx = await NftMarketplaceService.listNftToMarketplace(
+      connection,
+      usdSellerTradeState,
+      user2, // owner
+      mint,
+      marketplaceAccount,
+      buyerNftATA.address,
+      mintMTT,
+      root.publicKey,
+      programAsSigner,
+      TOKEN_PROGRAM_ID,
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      listPrice,
+      [],
+      new BN(300_000_000),
+      MOCK_PRICE_FEED,
+      null,
+      null,
+      false,
+      metadataAccount,
+    );
+    console.log('NFT listed pegged to USD: ', listNftTx);
+  });
+
+  it('Batch list NFTs', async () => {
+    batchNfts = [];
+    for (let i = 0; i < 3; i++) {
+      batchNfts.push(await createNft());
+    }
+
+    batchListItems = batchNfts.map((nft, i) => {
+      const listPrice = new BN((10 + i) * LAMPORTS_PER_SOL);
+      return {
+        mintNftAccount: nft.mint,
+        nftTokenAccount: nft.tokenAccount,
+        sellerTradeState: findListingAddress(root.publicKey, listPrice, nft.mint, nft.tokenAccount),
+        metadataAccount: nft.metadataAccount,
+        collectionStats: null,
+        listPrice,
+      };
+    });
+
+    const batchListTx = await NftMarketplaceService.batchList(
+      connection,
+      root, // seller
+      marketplaceAccount,
+      mintMTT,
+      root.publicKey,
+      programAsSigner,
+      TOKEN_PROGRAM_ID,
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      batchListItems,
+    );
+    console.log('NFTs batch listed: ', batchListTx);
+
+    for (const item of batchListItems) {
+      const nftTokenAccount = await getAccount(connection, item.nftTokenAccount);
+      expect(nftTokenAccount.delegate.toBase58()).to.equal(programAsSigner.toBase58());
+
+      const tradeStateInfo = await connection.getAccountInfo(item.sellerTradeState);
+      expect(tradeStateInfo).to.not.be.null;
+    }
+  });
+
+  it('Sweep batch listed NFTs', async () => {
+    const buyerNftAccounts: PublicKey[] = [];
+    for (const nft of batchNfts) {
+      const buyerNftAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        user2,
+        nft.mint,
+        user2.publicKey,
+      );
+      buyerNftAccounts.push(buyerNftAccount.address);
+    }
+
+    const sweepItems: SweepItem[] = batchListItems.map((item, i) => ({
+      seller: root.publicKey,
+      sellerTradeState: item.sellerTradeState,
+      mintNftAccount: item.mintNftAccount,
+      nftTokenAccount: item.nftTokenAccount,
+      buyerNftAccount: buyerNftAccounts[i],
+      sellerTokenAccount: sellerMTTATA.address,
+      metadataAccount: batchNfts[i].metadataAccount,
+      collectionStats: null,
+      priceObservations: null,
+      price: item.listPrice,
+    }));
+    // The last listing is quoted at a stale price and skipped
+    sweepItems[2].price = sweepItems[2].price.add(new BN(LAMPORTS_PER_SOL));
+
+    const sweepTx = await NftMarketplaceService.sweep(
+      connection,
+      user2, // buyer
+      buyerMTTATA.address,
+      marketplaceAccount,
+      mintMTT,
+      programAsSigner,
+      root.publicKey,
+      feeATA.address,
+      TOKEN_PROGRAM_ID,
+      SystemProgram.programId,
+      TOKEN_PROGRAM_ID,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      sweepItems,
+      new BN(25 * LAMPORTS_PER_SOL),
+      true,
+    );
+    console.log('NFTs swept: ', sweepTx);
+
+    for (let i = 0; i < 2; i++) {
+      const tradeStateInfo = await connection.getAccountInfo(batchListItems[i].sellerTradeState);
+      expect(tradeStateInfo).to.be.null;
+
+      const buyerNftAccount = await getAccount(connection, buyerNftAccounts[i]);
+      expect(Number(buyerNftAccount.amount)).to.equal(1);
+    }
+
+    const skippedTradeStateInfo = await connection.getAccountInfo(batchListItems[2].sellerTradeState);
+    expect(skippedTradeStateInfo).to.not.be.null;
+  });
+
+  it('List, cancel and buy a bundle of NFTs', async () => {
+    const bundleNfts = [await createNft(), await createNft()];
+    const bundleId = new BN(1);
+    const bundlePrice = new BN(20 * LAMPORTS_PER_SOL);
+    const [bundleListing] = findProgramAddressSync(
+      [
+        Buffer.from("MARKETPLACE_BUNDLE_LISTING"),
+        root.publicKey.toBuffer(),
+        marketplaceAccount.toBuffer(),
+        bundleId.toArrayLike(Buffer, 'le', 8),
+      ],
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+    console.log('Bundle listing: ', bundleListing.toBase58());
+
+    const bundleItems: BundleListItem[] = bundleNfts.map(nft => ({
+      mintNftAccount: nft.mint,
+      nftTokenAccount: nft.tokenAccount,
+      metadataAccount: nft.metadataAccount,
+      collectionStats: null,
+    }));
+
+    const listBundleTx = await NftMarketplaceService.listBundle(
+      connection,
+      bundleListing,
+      root, // seller
+      marketplaceAccount,
+      mintMTT,
+      root.publicKey,
+      programAsSigner,
+      TOKEN_PROGRAM_ID,
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      bundleId,
+      bundlePrice,
+      bundleItems,
+    );
+    console.log('Bundle listed: ', listBundleTx);
+
+    const cancelBundleTx = await NftMarketplaceService.cancelBundle(
+      connection,
+      bundleListing,
+      root, // seller
+      marketplaceAccount,
+      programAsSigner,
+      TOKEN_PROGRAM_ID,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      bundleNfts.map(nft => nft.tokenAccount),
+    );
+    console.log('Bundle cancelled: ', cancelBundleTx);
+
+    expect(await connection.getAccountInfo(bundleListing)).to.be.null;
+    for (const nft of bundleNfts) {
+      const nftTokenAccount = await getAccount(connection, nft.tokenAccount);
+      expect(nftTokenAccount.delegate).to.be.null;
+    }
+
+    const relistBundleTx = await NftMarketplaceService.listBundle(
+      connection,
+      bundleListing,
+      root, // seller
+      marketplaceAccount,
+      mintMTT,
+      root.publicKey,
+      programAsSigner,
+      TOKEN_PROGRAM_ID,
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      bundleId,
+      bundlePrice,
+      bundleItems,
+    );
+    console.log('Bundle relisted: ', relistBundleTx);
+
+    const saleItems: BundleSaleItem[] = [];
+    for (const nft of bundleNfts) {
+      const buyerNftAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        user2,
+        nft.mint,
+        user2.publicKey,
+      );
+      saleItems.push({
+        mintNftAccount: nft.mint,
+        nftTokenAccount: nft.tokenAccount,
+        buyerNftAccount: buyerNftAccount.address,
+        metadataAccount: nft.metadataAccount,
+        collectionStats: null,
+        priceObservations: null,
+      });
+    }
+
+    const executeBundleSaleTx = await NftMarketplaceService.executeBundleSale(
+      connection,
+      user2, // buyer
+      root.publicKey,
+      buyerMTTATA.address,
+      sellerMTTATA.address,
+      bundleListing,
+      marketplaceAccount,
+      mintMTT,
+      programAsSigner,
+      root.publicKey,
+      feeATA.address,
+      TOKEN_PROGRAM_ID,
+      SystemProgram.programId,
+      TOKEN_PROGRAM_ID,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      bundlePrice,
+      saleItems,
+    );
+    console.log('Bundle bought: ', executeBundleSaleTx);
+
+    for (const item of saleItems) {
+      const buyerNftAccount = await getAccount(connection, item.buyerNftAccount);
+      expect(Number(buyerNftAccount.amount)).to.equal(1);
+    }
+  });
+
+  it('Create, accept and cancel NFT swaps', async () => {
+    const findSwapAddress = (swapId: BN) => findProgramAddressSync(
+      [
+        Buffer.from("MARKETPLACE_SWAP"),
+        root.publicKey.toBuffer(),
+        swapId.toArrayLike(Buffer, 'le', 8),
+      ],
+      NFT_MARKETPLACE_PROGRAM_ID,
+    )[0];
+
+    // Swap an NFT of root for 5 MTT of user 2
+    const acceptedNft = await createNft();
+    const acceptedSwapId = new BN(1);
+    const acceptedSwap = findSwapAddress(acceptedSwapId);
+    console.log('Swap account: ', acceptedSwap.toBase58());
+
+    const acceptedEscrow = await getOrCreateAssociatedTokenAccount(
+      connection,
+      root,
+      acceptedNft.mint,
+      acceptedSwap,
+      true,
+    );
+
+    const createSwapTx = await NftMarketplaceService.createSwap(
+      connection,
+      acceptedSwap,
+      root, // maker
+      TOKEN_PROGRAM_ID,
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      acceptedSwapId,
+      user2.publicKey,
+      [{ mint: acceptedNft.mint, amount: new BN(1) }],
+      [{ mint: mintMTT, amount: new BN(5 * LAMPORTS_PER_SOL) }],
+      [{ source: acceptedNft.tokenAccount, destination: acceptedEscrow.address }],
+    );
+    console.log('Swap created: ', createSwapTx);
+
+    const takerNftATA = await getOrCreateAssociatedTokenAccount(
+      connection,
+      user2,
+      acceptedNft.mint,
+      user2.publicKey,
+    );
+
+    const acceptSwapTx = await NftMarketplaceService.acceptSwap(
+      connection,
+      acceptedSwap,
+      root.publicKey,
+      user2, // taker
+      TOKEN_PROGRAM_ID,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      [{ source: buyerMTTATA.address, destination: sellerMTTATA.address }],
+      [{ source: acceptedEscrow.address, destination: takerNftATA.address }],
+    );
+    console.log('Swap accepted: ', acceptSwapTx);
+
+    expect(await connection.getAccountInfo(acceptedSwap)).to.be.null;
+    expect(await connection.getAccountInfo(acceptedEscrow.address)).to.be.null;
+    const takerNftAccount = await getAccount(connection, takerNftATA.address);
+    expect(Number(takerNftAccount.amount)).to.equal(1);
+
+    // Offer another NFT, then take it back
+    const cancelledNft = await createNft();
+    const cancelledSwapId = new BN(2);
+    const cancelledSwap = findSwapAddress(cancelledSwapId);
+
+    const cancelledEscrow = await getOrCreateAssociatedTokenAccount(
+      connection,
+      root,
+      cancelledNft.mint,
+      cancelledSwap,
+      true,
+    );
+
+    const createCancelledSwapTx = await NftMarketplaceService.createSwap(
+      connection,
+      cancelledSwap,
+      root, // maker
+      TOKEN_PROGRAM_ID,
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      cancelledSwapId,
+      user2.publicKey,
+      [{ mint: cancelledNft.mint, amount: new BN(1) }],
+      [{ mint: mintMTT, amount: new BN(5 * LAMPORTS_PER_SOL) }],
+      [{ source: cancelledNft.tokenAccount, destination: cancelledEscrow.address }],
+    );
+    console.log('Swap created: ', createCancelledSwapTx);
+
+    const cancelSwapTx = await NftMarketplaceService.cancelSwap(
+      connection,
+      cancelledSwap,
+      root, // maker
+      TOKEN_PROGRAM_ID,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      [{ source: cancelledEscrow.address, destination: cancelledNft.tokenAccount }],
+    );
+    console.log('Swap cancelled: ', cancelSwapTx);
+
+    expect(await connection.getAccountInfo(cancelledSwap)).to.be.null;
+    expect(await connection.getAccountInfo(cancelledEscrow.address)).to.be.null;
+    const makerNftAccount = await getAccount(connection, cancelledNft.tokenAccount);
+    expect(Number(makerNftAccount.amount)).to.equal(1);
+  });
+
+  it('Buy NFT into claimable balance and claim it', async () => {
+    const nft = await createNft();
+    const listPrice = new BN(10 * LAMPORTS_PER_SOL);
+    const nftSellerTradeState = findListingAddress(root.publicKey, listPrice, nft.mint, nft.tokenAccount);
+
+    const listNftTx = await NftMarketplaceService.listNftToMarketplace(
+      connection,
+      nftSellerTradeState,
+      root, // owner
+      nft.mint,
+      marketplaceAccount,
+      nft.tokenAccount,
+      mintMTT,
+      root.publicKey,
+      programAsSigner,
+      TOKEN_PROGRAM_ID,
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      listPrice,
+      [],
+      null,
+      null,
+      null,
+      null,
+      true, // claimable payout
+      nft.metadataAccount,
+    );
+    console.log('NFT listed to marketplace: ', listNftTx);
+
+    // Currency token account of the program signer holding claimable balances
+    const vaultATA = await getOrCreateAssociatedTokenAccount(
+      connection,
+      root,
+      mintMTT,
+      programAsSigner,
+      true,
+    );
+    console.log('Vault account: ', vaultATA.address.toBase58());
+
+    const [claimableBalance] = findProgramAddressSync(
+      [
+        Buffer.from("MARKETPLACE_CLAIMABLE_BALANCE"),
+        marketplaceAccount.toBuffer(),
+        root.publicKey.toBuffer(),
+        mintMTT.toBuffer(),
+      ],
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+    console.log('Claimable balance: ', claimableBalance.toBase58());
+
+    const buyerNftAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      user2,
+      nft.mint,
+      user2.publicKey,
+    );
+
+    const buyNftTx = await NftMarketplaceService.buyNft(
+      connection,
+      user2,
+      root.publicKey,
+      buyerNftAccount.address,
+      buyerMTTATA.address,
+      null, // proceeds credited in the vault
+      nftSellerTradeState,
+      nft.mint,
+      marketplaceAccount,
+      nft.tokenAccount,
+      mintMTT,
+      programAsSigner,
+      root.publicKey,
+      feeATA.address,
+      TOKEN_PROGRAM_ID,
+      SystemProgram.programId,
+      TOKEN_PROGRAM_ID,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      listPrice,
+      [],
+      null,
+      null,
+      null,
+      null,
+      null,
+      null,
+      nft.metadataAccount,
+      [],
+      null,
+      vaultATA.address,
+      claimableBalance,
+    );
+    console.log('NFT bought into claimable balance: ', buyNftTx);
+
+    const vaultBalance = await getAccount(connection, vaultATA.address);
+    expect(Number(vaultBalance.amount)).to.be.greaterThan(0);
+
+    const claimTx = await NftMarketplaceService.claim(
+      connection,
+      claimableBalance,
+      root, // wallet
+      marketplaceAccount,
+      vaultATA.address,
+      sellerMTTATA.address,
+      mintMTT,
+      programAsSigner,
+      TOKEN_PROGRAM_ID,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      new BN(vaultBalance.amount.toString()),
+    );
+    console.log('Claimable balance claimed: ', claimTx);
+
+    const claimedVaultBalance = await getAccount(connection, vaultATA.address);
+    expect(Number(claimedVaultBalance.amount)).to.equal(0);
+  });
+
+  it('Migrate marketplace and listing to the latest layout', async () => {
+    // Both accounts were created with the latest layout, so migrating keeps them as is
+    const migrateMarketplaceTx = await NftMarketplaceService.migrateMarketplace(
+      connection,
+      marketplaceAccount,
+      root, // payer
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+    console.log('Marketplace migrated: ', migrateMarketplaceTx);
+
+    const marketplaceInfo = await connection.getAccountInfo(marketplaceAccount);
+    expect(marketplaceInfo.data.length).to.equal(1403);
+    expect(marketplaceInfo.data[8]).to.equal(1);
+
+    const sellerTradeStateAccount = batchListItems[2].sellerTradeState;
+    const migrateListingTx = await NftMarketplaceService.migrateListing(
+      connection,
+      sellerTradeStateAccount,
+      root, // payer
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+    console.log('Listing migrated: ', migrateListingTx);
+
+    const sellerTradeStateInfo = await connection.getAccountInfo(sellerTradeStateAccount);
+    expect(sellerTradeStateInfo.data.length).to.equal(361);
+    expect(sellerTradeStateInfo.data[8]).to.equal(1);
+  });
+
+  it('Change multisig signers through a proposal', async () => {
+    const multisigId = new BN(1);
+    const [multisig] = findProgramAddressSync(
+      [
+        Buffer.from("MARKETPLACE_MULTISIG"),
+        root.publicKey.toBuffer(),
+        multisigId.toArrayLike(Buffer, 'le', 8),
+      ],
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+    console.log('Multisig account: ', multisig.toBase58());
+
+    const createMultisigTx = await NftMarketplaceService.createMultisig(
+      connection,
+      multisig,
+      root, // creator
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      multisigId,
+      [root.publicKey, user2.publicKey],
+      2,
+    );
+    console.log('Multisig created: ', createMultisigTx);
+
+    // Lower the threshold to a single approval
+    const instruction = NftMarketplaceInstructionService.setMultisigSignersInstruction(
+      multisig,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      [root.publicKey, user2.publicKey],
+      1,
+    );
+
+    const [proposal] = findProgramAddressSync(
+      [
+        Buffer.from("MARKETPLACE_MULTISIG_PROPOSAL"),
+        multisig.toBuffer(),
+        new BN(0).toArrayLike(Buffer, 'le', 8),
+      ],
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+    console.log('Proposal account: ', proposal.toBase58());
+
+    const createProposalTx = await NftMarketplaceService.createProposal(
+      connection,
+      proposal,
+      multisig,
+      root, // proposer
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      instruction,
+    );
+    console.log('Proposal created: ', createProposalTx);
+
+    const approveProposalTx = await NftMarketplaceService.approveProposal(
+      connection,
+      proposal,
+      multisig,
+      user2, // signer
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+    console.log('Proposal approved: ', approveProposalTx);
+
+    const executeProposalTx = await NftMarketplaceService.executeProposal(
+      connection,
+      proposal,
+      multisig,
+      root, // payer
+      NFT_MARKETPLACE_PROGRAM_ID,
+      instruction,
+    );
+    console.log('Proposal executed: ', executeProposalTx);
+
+    // Threshold follows the creator, multisig id and bump
+    const multisigInfo = await connection.getAccountInfo(multisig);
+    expect(multisigInfo.data[8 + 32 + 8 + 1]).to.equal(1);
+  });
+
+  it('Queue, cancel and apply pending fee changes', async () => {
+    const [pendingChange] = findProgramAddressSync(
+      [
+        Buffer.from("MARKETPLACE_PENDING_CHANGE"),
+        marketplaceAccount.toBuffer(),
+        mintMTT.toBuffer(),
+      ],
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+    console.log('Pending change: ', pendingChange.toBase58());
+
+    const queuePendingChangeTx = await NftMarketplaceService.queuePendingChange(
+      connection,
+      pendingChange,
+      marketplaceAccount,
+      mintMTT,
+      feeATA.address,
+      root, // owner
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      500,
+      0,
+      new BN(0),
+      null,
+    );
+    console.log('Pending change queued: ', queuePendingChangeTx);
+
+    const cancelPendingChangeTx = await NftMarketplaceService.cancelPendingChange(
+      connection,
+      pendingChange,
+      marketplaceAccount,
+      root, // owner
+      root.publicKey,
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+    console.log('Pending change cancelled: ', cancelPendingChangeTx);
+
+    expect(await connection.getAccountInfo(pendingChange)).to.be.null;
+
+    // The change delay is still zero, so the queued change applies right away
+    const requeuePendingChangeTx = await NftMarketplaceService.queuePendingChange(
+      connection,
+      pendingChange,
+      marketplaceAccount,
+      mintMTT,
+      feeATA.address,
+      root, // owner
+      SystemProgram.programId,
+      NFT_MARKETPLACE_PROGRAM_ID,
+      250,
+      0,
+      new BN(0),
+      [{ recipient: root.publicKey, basisPoints: 10000 }],
+    );
+    console.log('Pending change queued: ', requeuePendingChangeTx);
+
+    const applyPendingChangeTx = await NftMarketplaceService.applyPendingChange(
+      connection,
+      pendingChange,
+      marketplaceAccount,
+      root.publicKey,
+      user2, // feePayer
+      NFT_MARKETPLACE_PROGRAM_ID,
+    );
+    console.log('Pending change applied: ', applyPendingChangeTx);
+
+    // Seller fee follows the version, currency mint and fee account
+    const marketplaceInfo = await connection.getAccountInfo(marketplaceAccount);
+    expect(marketplaceInfo.data.readUInt16LE(8 + 1 + 32 + 32)).to.equal(250);
+
+    // Further changes have to wait one minute once queued
+    const setChangeDelayTx = await NftMarketplaceService.setChangeDelay(
+      connection,
+      marketplaceAccount,
+      root, // owner
+      NFT_MARKETPLACE_PROGRAM_ID,
+      new BN(60),
+    );
+    console.log('Change delay set: ', setChangeDelayTx);
+  });
 
 
   // it('Create second NFT', async () => {